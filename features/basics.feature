@@ -70,7 +70,54 @@ Feature: Basic YAML schema
       ```
       type: foo
       ```
-    Then it should fail with "Unsupported type 'foo'!"
+    Then it should fail with "[2:7] Unsupported type 'foo'!"
+
+  Scenario: An unknown keyword should error with its position
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        name:
+          type: string
+          minLenght: 1
+      ```
+    Then it should fail with "[6:5] Unknown keyword 'minLenght'"
+
+  Scenario: A keyword for another type should error with its position
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        age:
+          type: integer
+          minLength: 1
+      ```
+    Then it should fail with "[6:5] Unsupported keyword 'minLength' in a `type: integer` schema"
+
+  Scenario: Extension keywords are allowed
+    Given a YAML schema:
+      ```
+      type: object
+      x-kubernetes-preserve-unknown-fields: true
+      properties:
+        name:
+          type: string
+          x-order: 1
+      ```
+    Then it should accept:
+      ```
+      name: test
+      ```
+
+  Scenario: A required property that isn't a string should error with its position
+    Given a YAML schema:
+      ```
+      type: object
+      required:
+        - name
+        - 42
+      ```
+    Then it should fail with "[5:5] Expected a string value, but got: Integer(42)"
 
   Scenario: "type: string" should accept strings
     Given a YAML schema:
@@ -147,4 +194,122 @@ Feature: Basic YAML schema
       foo: 42
       bar: "I'm a string"
       ```
-    And the error message should be "[1:6] .foo: Expected a string, but got: Integer(42)"
+    And the error message should be "[1:6] .foo: Expected a string, but got: Integer(42) (/properties/foo/type)"
+
+  Scenario: Multiple types
+    Given a YAML schema:
+      ```
+      type: [string, number]
+      ```
+    Then it should accept:
+      ```
+      "I'm a string"
+      ```
+    And it should accept:
+      ```
+      42
+      ```
+    But it should NOT accept:
+      ```
+      an:
+        - arbitrarily
+        - nested
+      data: structure
+      ```
+    And it should NOT accept:
+      ```
+      - 1
+      - 2
+      ```
+    And the error message should be "[1:1] .: Expected one of [string, number], but got: [1, 2] (/type)"
+    And it should NOT accept:
+      ```
+      true
+      ```
+    And the error message should be "[1:1] .: Expected one of [string, number], but got: true (/type)"
+
+  Scenario: Nullable types
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        name:
+          type: [string, "null"]
+          minLength: 3
+        replicas:
+          type: [integer, null]
+          minimum: 1
+        labels:
+          type: [object, "null"]
+          properties:
+            app:
+              type: string
+      ```
+    Then it should accept:
+      ```
+      name: web
+      replicas: 3
+      labels:
+        app: web
+      ```
+    And it should accept:
+      ```
+      name: null
+      replicas: null
+      labels: null
+      ```
+    But it should NOT accept:
+      ```
+      name: ab
+      ```
+    And the error message should be "[1:7] .name: String is too short! (min length: 3) (/properties/name/minLength)"
+    And it should NOT accept:
+      ```
+      replicas: 0
+      ```
+    And the error message should be "[1:11] .replicas: Number is too small! (/properties/replicas/minimum)"
+    And it should NOT accept:
+      ```
+      labels:
+        app: 42
+      ```
+    And the error message should be "[2:8] .labels.app: Expected a string, but got: Integer(42) (/properties/labels/properties/app/type)"
+    And it should NOT accept:
+      ```
+      name: 42
+      ```
+    And the error message should be "[1:7] .name: Expected one of [string, null], but got: 42 (/properties/name/type)"
+
+  Scenario: Annotations don't affect validation
+    Given a YAML schema:
+      ```
+      title: Deployment
+      description: A deployment of a web service
+      $comment: Keep in sync with the chart
+      type: object
+      properties:
+        replicas:
+          description: The number of replicas to run
+          type: integer
+          default: 1
+          examples: [1, 3]
+        image:
+          description: Use `containers` instead
+          deprecated: true
+          readOnly: true
+          type: string
+      additionalProperties:
+        description: Any other setting
+        type: string
+      ```
+    Then it should accept:
+      ```
+      replicas: 3
+      image: web:1.0
+      region: eu
+      ```
+    But it should NOT accept:
+      ```
+      image: 42
+      ```
+    And the error message should be "[1:8] .image: Expected a string, but got: Integer(42) (/properties/image/type)"
@@ -24,3 +24,224 @@ Feature: CLI usage
       ys -f tests/fixtures/schema.yaml tests/fixtures/invalid.yaml
       ```
     Then it should exit with status code 1
+
+  Scenario: Output the validation results in a JSON Schema output format
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/invalid.yaml --output basic
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      {
+        "valid": false,
+        "errors": [
+          {
+            "valid": false,
+            "keywordLocation": "/properties/foo/type",
+            "instanceLocation": "/foo",
+            "error": "Expected a string, but got: Integer(42)"
+          },
+          {
+            "valid": false,
+            "keywordLocation": "/properties/bar/type",
+            "instanceLocation": "/bar",
+            "error": "Expected a number, but got: String(\"I'm a string\")"
+          }
+        ]
+      }
+      ```
+
+  Scenario: Report validation errors as JSON
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/invalid.yaml --format json
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      [
+        {
+          "file": "tests/fixtures/invalid.yaml",
+          "document": 0,
+          "line": 1,
+          "column": 6,
+          "path": "foo",
+          "message": "Expected a string, but got: Integer(42)"
+        },
+        {
+          "file": "tests/fixtures/invalid.yaml",
+          "document": 0,
+          "line": 2,
+          "column": 6,
+          "path": "bar",
+          "message": "Expected a number, but got: String(\"I'm a string\")"
+        }
+      ]
+      ```
+
+  Scenario: Report validation errors as GitHub Actions annotations
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/invalid.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/invalid.yaml,line=1,col=6::.foo: Expected a string, but got: Integer(42)
+      ::error file=tests/fixtures/invalid.yaml,line=2,col=6::.bar: Expected a number, but got: String("I'm a string")
+      ```
+
+  Scenario: Validate the files matching a glob pattern
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/*valid.yaml --format junit
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      <?xml version="1.0" encoding="UTF-8"?>
+      <testsuite name="ys" tests="2" failures="1" errors="0">
+        <testcase name="tests/fixtures/invalid.yaml" classname="ys">
+          <failure message="2 validation errors" type="ValidationError">[1:6] .foo: Expected a string, but got: Integer(42) (/properties/foo/type)
+      [2:6] .bar: Expected a number, but got: String(&quot;I&apos;m a string&quot;) (/properties/bar/type)</failure>
+        </testcase>
+        <testcase name="tests/fixtures/valid.yaml" classname="ys"/>
+      </testsuite>
+      ```
+
+  Scenario: Match a glob pattern that starts with ./
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml ./tests/fixtures/*valid.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/invalid.yaml,line=1,col=6::.foo: Expected a string, but got: Integer(42)
+      ::error file=tests/fixtures/invalid.yaml,line=2,col=6::.bar: Expected a number, but got: String("I'm a string")
+      ```
+
+  Scenario: Report a file that isn't valid YAML as failing
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/malformed --format json
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      [
+        {
+          "file": "tests/fixtures/malformed/malformed.yaml",
+          "document": 0,
+          "line": null,
+          "column": null,
+          "path": "",
+          "message": "YAML parsing error: while parsing a flow sequence, expected ',' or ']' at byte 10 line 2 column 1"
+        }
+      ]
+      ```
+
+  Scenario: Every schema must pass by default
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml -f tests/fixtures/multiple/required.yaml tests/fixtures/valid.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/valid.yaml,line=1,col=1::.: Required property 'baz' is missing!
+      ```
+
+  Scenario: Reference the other schemas by their $id
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/multiple/server.yaml -f tests/fixtures/multiple/port.yaml --schema-mode refs tests/fixtures/multiple/server-invalid.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/multiple/server-invalid.yaml,line=1,col=7::.port: Number is too big!
+      ```
+
+  Scenario: Validate every document in a multi-document file
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/schema.yaml tests/fixtures/stream.yaml --all-documents --format json
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      [
+        {
+          "file": "tests/fixtures/stream.yaml",
+          "document": 1,
+          "line": 4,
+          "column": 6,
+          "path": "foo",
+          "message": "Expected a string, but got: Integer(42)"
+        }
+      ]
+      ```
+
+  Scenario: Validate standard input
+    When the following command is run with `tests/fixtures/invalid.yaml` piped to stdin:
+      ```
+      ys -f tests/fixtures/schema.yaml - --stdin-filename rendered.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=rendered.yaml,line=1,col=6::.foo: Expected a string, but got: Integer(42)
+      ::error file=rendered.yaml,line=2,col=6::.bar: Expected a number, but got: String("I'm a string")
+      ```
+
+  Scenario: Check a schema against the meta-schema
+    When the following command is run:
+      ```
+      ys --format github check-schema tests/fixtures/check-schema/invalid-schema.yaml
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/check-schema/invalid-schema.yaml,line=4,col=11::.properties.name.type: None of the schemas in `anyOf` matched!
+      ::error file=tests/fixtures/check-schema/invalid-schema.yaml,line=7,col=14::.properties.port: Additional property 'minimun' is not allowed!
+      ```
+
+  Scenario: Check a schema with a multipleOf of zero
+    When the following command is run:
+      ```
+      ys --format github check-schema tests/fixtures/check-schema/zero-multiple.yaml
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/check-schema/zero-multiple.yaml,line=5,col=17::.properties.replicas.multipleOf: Number is too small!
+      ```
+
+  Scenario: Unknown schema keywords are warned about by default
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/extensions/schema.yaml tests/fixtures/extensions/invalid.yaml --format github
+      ```
+    Then it should exit with status code 1
+    And it should output:
+      ```
+      ::error file=tests/fixtures/extensions/invalid.yaml,line=2,col=11::.replicas: Expected a number, but got: String("many")
+      ```
+    And it should print to stderr:
+      ```
+      tests/fixtures/extensions/schema.yaml: warning: [6:5] Unknown keyword 'minLenght' is ignored
+      tests/fixtures/extensions/schema.yaml: warning: [9:5] Keyword 'minLength' doesn't apply to a `type: integer` schema and is ignored
+      ```
+
+  Scenario: Unknown schema keywords are an error with --strict-schema
+    When the following command is run:
+      ```
+      ys -f tests/fixtures/extensions/schema.yaml tests/fixtures/extensions/invalid.yaml --strict-schema
+      ```
+    Then it should exit with status code 1
+    And it should print to stderr:
+      ```
+      Validation failed: Failed to read YAML schema file: tests/fixtures/extensions/schema.yaml: [6:5] Unknown keyword 'minLenght'
+      ```
@@ -1,5 +1,39 @@
 Feature: Schema Composition
 
+  Scenario: allOf
+    Given a YAML schema:
+      ```
+      allOf:
+        - type: object
+          properties:
+            name:
+              type: string
+            replicas:
+              type: integer
+          required:
+            - name
+        - type: object
+          properties:
+            replicas:
+              type: integer
+              minimum: 2
+      ```
+    Then it should accept:
+      ```
+      name: web
+      replicas: 3
+      ```
+    But it should NOT accept:
+      ```
+      replicas: 3
+      ```
+    And it should NOT accept:
+      ```
+      name: web
+      replicas: 1
+      ```
+    And the error message should be "[2:11] .replicas: Number is too small! (/allOf/1/properties/replicas/minimum)"
+
   Scenario: anyOf
     Given a YAML schema:
       ```
@@ -170,3 +204,112 @@ Feature: Schema Composition
       ```
       "I am a string"
       ```
+
+  Scenario: if then else
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        kind:
+          type: string
+        replicas:
+          type: integer
+        schedule:
+          type: string
+      if:
+        type: object
+        properties:
+          kind:
+            const: Deployment
+      then:
+        type: object
+        required:
+          - replicas
+      else:
+        type: object
+        required:
+          - schedule
+      ```
+    Then it should accept:
+      ```
+      kind: Deployment
+      replicas: 3
+      ```
+    And it should accept:
+      ```
+      kind: CronJob
+      schedule: "0 * * * *"
+      ```
+    But it should NOT accept:
+      ```
+      kind: Deployment
+      ```
+    And the error message should be "[1:1] .: Required property 'replicas' is missing! (/then/required)"
+    And it should NOT accept:
+      ```
+      kind: CronJob
+      replicas: 3
+      ```
+    And the error message should be "[1:1] .: Required property 'schedule' is missing! (/else/required)"
+
+  Scenario: if without else
+    Given a YAML schema:
+      ```
+      if:
+        type: string
+      then:
+        type: string
+        minLength: 3
+      ```
+    Then it should accept:
+      ```
+      42
+      ```
+    And it should accept:
+      ```
+      foo
+      ```
+    But it should NOT accept:
+      ```
+      fo
+      ```
+
+  Scenario: if, then and else without a type
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        kind:
+          type: string
+      if:
+        properties:
+          kind:
+            const: Deployment
+        required:
+          - kind
+      then:
+        required:
+          - replicas
+      else:
+        required:
+          - schedule
+      ```
+    Then it should accept:
+      ```
+      kind: Deployment
+      replicas: 3
+      ```
+    And it should accept:
+      ```
+      kind: CronJob
+      schedule: "0 * * * *"
+      ```
+    But it should NOT accept:
+      ```
+      kind: Deployment
+      ```
+    And the error message should be "[1:1] .: Required property 'replicas' is missing! (/then/required)"
+    And it should NOT accept:
+      ```
+      replicas: 3
+      ```
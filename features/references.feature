@@ -0,0 +1,195 @@
+Feature: Schema references
+
+  Scenario: $ref to $defs
+    Given a YAML schema:
+      ```
+      $defs:
+        address:
+          type: object
+          properties:
+            street:
+              type: string
+            city:
+              type: string
+          required:
+            - street
+            - city
+      type: object
+      properties:
+        billing_address:
+          $ref: "#/$defs/address"
+        shipping_address:
+          $ref: "#/$defs/address"
+      ```
+    Then it should accept:
+      ```
+      billing_address:
+        street: 1600 Pennsylvania Avenue NW
+        city: Washington
+      shipping_address:
+        street: 221B Baker Street
+        city: London
+      ```
+    But it should NOT accept:
+      ```
+      billing_address:
+        street: 1600 Pennsylvania Avenue NW
+      ```
+    And the error message should be "[2:3] .billing_address: Required property 'city' is missing! (/properties/billing_address/$ref/required)"
+
+  Scenario: $ref to legacy definitions
+    Given a YAML schema:
+      ```
+      definitions:
+        port:
+          type: integer
+          minimum: 1
+          maximum: 65535
+      type: object
+      properties:
+        port:
+          $ref: "#/definitions/port"
+      ```
+    Then it should accept:
+      ```
+      port: 8080
+      ```
+    But it should NOT accept:
+      ```
+      port: 65536
+      ```
+
+  Scenario: Recursive schema
+    Given a YAML schema:
+      ```
+      $ref: "#/$defs/node"
+      $defs:
+        node:
+          type: object
+          properties:
+            value:
+              type: integer
+            children:
+              type: array
+              items:
+                $ref: "#/$defs/node"
+      ```
+    Then it should accept:
+      ```
+      value: 1
+      children:
+        - value: 2
+        - value: 3
+          children:
+            - value: 4
+      ```
+    But it should NOT accept:
+      ```
+      value: 1
+      children:
+        - value: 2
+          children:
+            - value: four
+      ```
+
+  Scenario: $ref to the root schema
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        name:
+          type: string
+        parent:
+          anyOf:
+            - type: null
+            - $ref: "#"
+      ```
+    Then it should accept:
+      ```
+      name: child
+      parent:
+        name: parent
+        parent: null
+      ```
+    But it should NOT accept:
+      ```
+      name: child
+      parent:
+        name: 42
+      ```
+
+  Scenario: $ref to another file
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        server:
+          $ref: tests/fixtures/refs/server.yaml
+      ```
+    Then it should accept:
+      ```
+      server:
+        listen:
+          host: 0.0.0.0
+          port: 8080
+        admin_port: 9090
+        upstreams:
+          - name: backend
+            endpoint:
+              host: backend.local
+              port: 3000
+      ```
+    But it should NOT accept:
+      ```
+      server:
+        listen:
+          host: 0.0.0.0
+          port: 80800
+      ```
+    And it should NOT accept:
+      ```
+      server:
+        upstreams:
+          - name: backend
+            endpoint:
+              host: backend.local
+      ```
+
+  Scenario: $ref with other keywords alongside it
+    Given a YAML schema:
+      ```
+      $defs:
+        name:
+          type: string
+          maxLength: 8
+      type: object
+      properties:
+        name:
+          $ref: "#/$defs/name"
+          minLength: 1
+      ```
+    Then it should accept:
+      ```
+      name: web
+      ```
+    But it should NOT accept:
+      ```
+      name: ""
+      ```
+    And it should NOT accept:
+      ```
+      name: webserver
+      ```
+
+  Scenario: $ref to a missing definition
+    Given a YAML schema:
+      ```
+      $defs:
+        name:
+          type: string
+      type: object
+      properties:
+        name:
+          $ref: "#/$defs/nope"
+      ```
+    Then it should fail with "[8:11] Cannot resolve reference: #/$defs/nope"
@@ -221,3 +221,156 @@ Feature: Arrays
       - 4
       - 5
       ```
+
+  Scenario: minContains and maxContains
+    Given a YAML schema:
+      ```
+      type: array
+      contains:
+        type: number
+      minContains: 2
+      maxContains: 3
+      ```
+    Then it should NOT accept:
+      ```
+      - apple
+      - orange
+      - 2
+      ```
+    And the error message should be "[1:1] .: Array contains 1 matching items, but at least 2 are required! (/minContains)"
+    But it should accept:
+      ```
+      - apple
+      - orange
+      - 2
+      - 4
+      ```
+    And it should accept:
+      ```
+      - apple
+      - orange
+      - 2
+      - 4
+      - 8
+      ```
+    But it should NOT accept:
+      ```
+      - apple
+      - orange
+      - 2
+      - 4
+      - 8
+      - 16
+      ```
+    And the error message should be "[1:1] .: Array contains 4 matching items, but at most 3 are allowed! (/maxContains)"
+
+  Scenario: minContains of zero
+    Given a YAML schema:
+      ```
+      type: array
+      contains:
+        type: number
+      minContains: 0
+      ```
+    Then it should accept:
+      ```
+      - life
+      - universe
+      - everything
+      ```
+
+  Scenario: Length
+    Given a YAML schema:
+      ```
+      type: array
+      minItems: 2
+      maxItems: 3
+      ```
+    Then it should NOT accept:
+      ```
+      []
+      ```
+    And it should NOT accept:
+      ```
+      - 1
+      ```
+    And the error message should be "[1:1] .: Array has too few items! Minimum is 2! (/minItems)"
+    But it should accept:
+      ```
+      - 1
+      - 2
+      ```
+    And it should accept:
+      ```
+      - 1
+      - 2
+      - 3
+      ```
+    But it should NOT accept:
+      ```
+      - 1
+      - 2
+      - 3
+      - 4
+      ```
+    And the error message should be "[1:1] .: Array has too many items! Maximum is 3! (/maxItems)"
+
+  Scenario: Uniqueness
+    Given a YAML schema:
+      ```
+      type: array
+      uniqueItems: true
+      ```
+    Then it should accept:
+      ```
+      - 1
+      - 2
+      - 3
+      - 4
+      - 5
+      ```
+    But it should NOT accept:
+      ```
+      - 1
+      - 2
+      - 3
+      - 3
+      - 4
+      ```
+    And the error message should be "[4:3] .: Array items are not unique! Item 3 is equal to item 2! (/uniqueItems)"
+    # Numbers with the same value are equal, regardless of how they're written
+    And it should NOT accept:
+      ```
+      - 1
+      - 1.0
+      ```
+    # Mappings are compared by their keys and values, regardless of key order
+    And it should NOT accept:
+      ```
+      - name: web
+        port: 80
+      - port: 80
+        name: web
+      ```
+    And it should NOT accept:
+      ```
+      - [a, [b, c]]
+      - [a, [b, c]]
+      ```
+    But it should accept:
+      ```
+      - name: web
+        port: 80
+      - name: web
+        port: 8080
+      ```
+    And it should accept:
+      ```
+      - [a, [b, c]]
+      - [a, [c, b]]
+      ```
+    # An empty array always passes
+    And it should accept:
+      ```
+      []
+      ```
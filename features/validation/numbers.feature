@@ -48,3 +48,29 @@ Feature: Numeric types
       ```
       23
       ```
+
+  Scenario: Exclusive bounds
+    Given a YAML schema:
+      ```
+      type: number
+      exclusiveMinimum: 0
+      exclusiveMaximum: 1.5
+      ```
+    Then it should accept:
+      ```
+      1
+      ```
+    And it should accept:
+      ```
+      0.5
+      ```
+    But it should NOT accept:
+      ```
+      0
+      ```
+    And the error message should be "[1:1] .: Number is too small! (/exclusiveMinimum)"
+    And it should NOT accept:
+      ```
+      1.5
+      ```
+    And the error message should be "[1:1] .: Number is too big! (/exclusiveMaximum)"
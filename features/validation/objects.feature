@@ -213,6 +213,11 @@ Feature: Object types
       ```
       keyword: 42
       ```
+    # Keys that match a pattern aren't additional properties
+    And it should accept:
+      ```
+      I_0: 42
+      ```
 
   Scenario: Required properties
     Given a YAML schema:
@@ -273,7 +278,7 @@ Feature: Object types
       ```
       -001 invalid: "value"
       ```
-    And the error message should be "[1:1] .: Property name '-001 invalid' does not match pattern '^[A-Za-z_][A-Za-z0-9_]*$'"
+    And the error message should be "[1:1] .: Property name '-001 invalid' does not match pattern '^[A-Za-z_][A-Za-z0-9_]*$' (/propertyNames)"
 
   Scenario: Size
     Given a YAML schema:
@@ -308,3 +313,135 @@ Feature: Object types
       c: 2
       d: 3
       ```
+
+  Scenario: dependentRequired
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        name:
+          type: string
+        tls:
+          type: boolean
+        cert:
+          type: string
+        key:
+          type: string
+      dependentRequired:
+        tls:
+          - cert
+          - key
+      ```
+    Then it should accept:
+      ```
+      name: web
+      ```
+    And it should accept:
+      ```
+      name: web
+      tls: true
+      cert: server.crt
+      key: server.key
+      ```
+    But it should NOT accept:
+      ```
+      name: web
+      tls: true
+      cert: server.crt
+      ```
+    And the error message should be "[2:1] .: Property 'key' is required when 'tls' is present! (/dependentRequired)"
+
+  Scenario: dependentSchemas
+    Given a YAML schema:
+      ```
+      type: object
+      properties:
+        name:
+          type: string
+        credit_card:
+          type: number
+      dependentSchemas:
+        credit_card:
+          type: object
+          properties:
+            billing_address:
+              type: string
+          required:
+            - billing_address
+      ```
+    Then it should accept:
+      ```
+      name: John Doe
+      credit_card: 5555555555555555
+      billing_address: 555 Debtor's Lane
+      ```
+    And it should accept:
+      ```
+      name: John Doe
+      billing_address: 555 Debtor's Lane
+      ```
+    But it should NOT accept:
+      ```
+      name: John Doe
+      credit_card: 5555555555555555
+      ```
+    And the error message should be "[2:1] .: Required property 'billing_address' is missing! (/dependentSchemas/credit_card/required)"
+    And it should NOT accept:
+      ```
+      name: John Doe
+      credit_card: 5555555555555555
+      billing_address: 555
+      ```
+    And the error message should be "[3:18] .billing_address: Expected a string, but got: Integer(555) (/dependentSchemas/credit_card/properties/billing_address/type)"
+
+  Scenario: dependencies (draft-07)
+    Given a YAML schema:
+      ```
+      type: object
+      dependencies:
+        tls:
+          - cert
+        credit_card:
+          type: object
+          required:
+            - billing_address
+      ```
+    Then it should accept:
+      ```
+      tls: true
+      cert: server.crt
+      ```
+    But it should NOT accept:
+      ```
+      tls: true
+      ```
+    And the error message should be "[1:1] .: Property 'cert' is required when 'tls' is present! (/dependentRequired)"
+    And it should NOT accept:
+      ```
+      credit_card: 5555555555555555
+      ```
+
+  Scenario: dependencies with a schema without a type (draft-07)
+    Given a YAML schema:
+      ```
+      type: object
+      dependencies:
+        credit_card:
+          required:
+            - billing_address
+      ```
+    Then it should accept:
+      ```
+      credit_card: 5555555555555555
+      billing_address: 555 Debtor's Lane
+      ```
+    But it should NOT accept:
+      ```
+      credit_card: 5555555555555555
+      ```
+    And the error message should be "[1:1] .: Required property 'billing_address' is missing! (/dependentSchemas/credit_card/required)"
+    And it should NOT accept:
+      ```
+      1: one
+      credit_card: 5555555555555555
+      ```
@@ -68,3 +68,94 @@ Feature: String validation
       ```
       "(800)FLOWERS"
       ```
+
+  Scenario: format is only an annotation by default
+    Given a YAML schema:
+      ```
+      type: string
+      format: email
+      ```
+    Then it should accept:
+      ```
+      "user@example.com"
+      ```
+    And it should accept:
+      ```
+      "not an email"
+      ```
+
+  Scenario: format assertion
+    Given format assertion is enabled
+    And a YAML schema:
+      ```
+      type: string
+      format: date
+      ```
+    Then it should accept:
+      ```
+      "2024-02-29"
+      ```
+    But it should NOT accept:
+      ```
+      "2023-02-29"
+      ```
+    And the error message should be "[1:1] .: String is not a valid date! (/format)"
+    And it should NOT accept:
+      ```
+      "tomorrow"
+      ```
+
+  Scenario: unknown formats are ignored
+    Given format assertion is enabled
+    And a YAML schema:
+      ```
+      type: string
+      format: semver
+      ```
+    Then it should accept:
+      ```
+      "anything"
+      ```
+
+  Scenario: unknown formats can be errors
+    Given format assertion is enabled
+    And unknown formats are errors
+    And a YAML schema:
+      ```
+      type: string
+      format: semver
+      ```
+    Then it should NOT accept:
+      ```
+      "1.2.3"
+      ```
+    And the error message should be "[1:1] .: Unknown format: semver! (/format)"
+
+  Scenario: format assertion with non-ASCII digits
+    Given format assertion is enabled
+    And a YAML schema:
+      ```
+      type: object
+      properties:
+        date:
+          type: string
+          format: date
+        time:
+          type: string
+          format: time
+      ```
+    Then it should accept:
+      ```
+      date: "2024-02-29"
+      time: "12:00:00Z"
+      ```
+    But it should NOT accept:
+      ```
+      date: "٢٠٢٤-٠٢-٢٩"
+      ```
+    And the error message should be "[1:7] .date: String is not a valid date! (/properties/date/format)"
+    And it should NOT accept:
+      ```
+      time: "١٢:00:00Z"
+      ```
+    And the error message should be "[1:7] .time: String is not a valid time! (/properties/time/format)"
@@ -1,11 +1,27 @@
+use std::collections::HashSet;
+use std::io::IsTerminal;
+use std::path::Path;
+
 use clap::Parser;
 use clap::Subcommand;
 use eyre::Context;
 use eyre::Result;
+use walkdir::WalkDir;
 
+use yaml_schema::check_schema;
+use yaml_schema::loader;
 use yaml_schema::version;
 use yaml_schema::Engine;
+use yaml_schema::EngineOptions;
+use yaml_schema::FileSystemResolver;
+use yaml_schema::IdResolver;
+use yaml_schema::LoaderOptions;
+use yaml_schema::OutputFormat;
+use yaml_schema::OutputUnit;
+use yaml_schema::Report;
+use yaml_schema::ReportFormat;
 use yaml_schema::RootSchema;
+use yaml_schema::UnknownKeywords;
 
 #[derive(Parser, Debug, Default)]
 #[command(name = "ys")]
@@ -17,77 +33,419 @@ pub struct Opts {
     /// The command to run
     #[command(subcommand)]
     pub command: Option<Commands>,
-    /// The schema to validate against
+    /// The schema to validate against. Can be given more than once, see --schema-mode
     #[arg(short = 'f', long = "schema")]
     pub schemas: Vec<String>,
-    /// Specify this flag to exit (1) as soon as any error is encountered
+    /// How to use more than one schema
+    #[arg(long = "schema-mode", value_enum, default_value_t)]
+    pub schema_mode: SchemaMode,
+    /// Fail if a schema has an unknown keyword, rather than warning about it. Extension
+    /// keywords starting with `x-` are always allowed
+    #[arg(long = "strict-schema", default_value = "false")]
+    pub strict_schema: bool,
+    /// Specify this flag to exit (1) as soon as any error is encountered, without validating
+    /// any more files
     #[arg(long = "fail-fast", default_value = "false")]
     pub fail_fast: bool,
-    /// The YAML file to validate
-    pub file: Option<String>,
+    /// Validate every document in a multi-document YAML file, rather than only the first
+    #[arg(long = "all-documents", default_value = "false")]
+    pub all_documents: bool,
+    /// Warn when a YAML file uses a property that the schema marks as `deprecated: true`
+    #[arg(long = "warn-deprecated", default_value = "false")]
+    pub warn_deprecated: bool,
+    /// Print the validation results to stdout in a JSON Schema output format
+    #[arg(long = "output", value_enum)]
+    pub output: Option<OutputArg>,
+    /// The format to report validation errors in. Text is written to stderr, other formats
+    /// to stdout
+    #[arg(long = "format", value_enum, default_value_t)]
+    pub format: FormatArg,
+    /// The name to report errors in standard input under
+    #[arg(long = "stdin-filename", default_value = "<stdin>")]
+    pub stdin_filename: String,
+    /// The YAML files to validate. Directories are searched recursively for *.yaml and *.yml
+    /// files, and glob patterns (e.g. 'k8s/**/*.yaml') are expanded. Use '-' to read from
+    /// standard input, which is also read if no files are given and it isn't a terminal
+    pub files: Vec<String>,
+}
+
+/// How `ys` uses more than one `-f` schema
+#[derive(Clone, Copy, Debug, Default, PartialEq, clap::ValueEnum)]
+pub enum SchemaMode {
+    /// Every schema must pass, as if they were combined with `allOf`
+    #[default]
+    AllOf,
+    /// Only the first schema must pass, and the others can be referenced from it by their `$id`
+    Refs,
+}
+
+/// The formats that `ys` can report validation errors in, for `--format`
+#[derive(Clone, Copy, Debug, Default, PartialEq, clap::ValueEnum)]
+pub enum FormatArg {
+    /// `file: [line:col] .path: message` lines
+    #[default]
+    Text,
+    /// A JSON array of errors
+    Json,
+    /// A YAML sequence of errors
+    Yaml,
+    /// A SARIF 2.1.0 log, for code scanning tools
+    Sarif,
+    /// A JUnit XML report, with a test case for each file
+    Junit,
+    /// GitHub Actions workflow commands, which annotate the errors in pull requests
+    Github,
+    /// Code frames showing the lines of each file that failed, with the values underlined
+    Pretty,
+}
+
+impl From<FormatArg> for ReportFormat {
+    fn from(format: FormatArg) -> Self {
+        match format {
+            FormatArg::Text => ReportFormat::Text,
+            FormatArg::Json => ReportFormat::Json,
+            FormatArg::Yaml => ReportFormat::Yaml,
+            FormatArg::Sarif => ReportFormat::Sarif,
+            FormatArg::Junit => ReportFormat::Junit,
+            FormatArg::Github => ReportFormat::Github,
+            FormatArg::Pretty => ReportFormat::Pretty,
+        }
+    }
+}
+
+/// The JSON Schema output formats, for `--output`
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum OutputArg {
+    /// Only whether the document is valid
+    Flag,
+    /// A flat list of errors
+    Basic,
+    /// Errors nested by the structure of the schema, where schemas with a single error are
+    /// collapsed into that error
+    Detailed,
+    /// Errors nested by the structure of the schema, without collapsing
+    Verbose,
+}
+
+impl From<OutputArg> for OutputFormat {
+    fn from(output: OutputArg) -> Self {
+        match output {
+            OutputArg::Flag => OutputFormat::Flag,
+            OutputArg::Basic => OutputFormat::Basic,
+            OutputArg::Detailed => OutputFormat::Detailed,
+            OutputArg::Verbose => OutputFormat::Verbose,
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
 pub enum Commands {
     #[command(about = "Display the ys version")]
     Version,
+    #[command(about = "Check schemas against the YAML Schema meta-schema")]
+    CheckSchema {
+        /// The schema files to check
+        #[arg(required = true)]
+        schemas: Vec<String>,
+    },
 }
 
 /// The main entrypoint function of the ys executable
 fn main() {
     env_logger::init();
     let opts = Opts::parse();
-    if let Some(comand) = opts.command {
-        match comand {
-            Commands::Version => {
-                println!("ys {}", version());
-            }
+    let result = match &opts.command {
+        Some(Commands::Version) => {
+            println!("ys {}", version());
+            return;
         }
-    } else {
-        match command_validate(opts) {
-            Ok(return_code) => {
-                std::process::exit(return_code);
-            }
-            Err(e) => {
-                eprintln!("Validation failed: {}", e);
-                std::process::exit(1);
-            }
+        Some(Commands::CheckSchema { schemas }) => {
+            command_check_schema(schemas, opts.format.into())
+        }
+        None => command_validate(opts),
+    };
+    match result {
+        Ok(return_code) => {
+            std::process::exit(return_code);
+        }
+        Err(e) => {
+            eprintln!("Validation failed: {}", error_chain(&e));
+            std::process::exit(1);
         }
     }
 }
 
+/// The `ys check-schema` command
+fn command_check_schema(schema_filenames: &[String], format: ReportFormat) -> Result<i32> {
+    let mut report = Report::new();
+    for schema_filename in schema_filenames {
+        let source = std::fs::read_to_string(schema_filename)
+            .wrap_err_with(|| format!("Failed to read YAML schema file: {}", schema_filename))?;
+        let errors = check_schema(&source)
+            .wrap_err_with(|| format!("Failed to check YAML schema file: {}", schema_filename))?;
+        report.add_file_with_source(schema_filename, source, errors);
+    }
+    print_report(&report, format);
+    Ok(if report.has_errors() { 1 } else { 0 })
+}
+
+/// The path that stands for standard input
+const STDIN: &str = "-";
+
 /// The `ys validate` command
 fn command_validate(opts: Opts) -> Result<i32> {
-    // Currently, we only support a single schema file
-    // TODO: Support multiple schema files
     if opts.schemas.is_empty() {
         return Err(eyre::eyre!("No schema file(s) specified"));
     }
-    if opts.file.is_none() {
+    let files = if opts.files.is_empty() && !std::io::stdin().is_terminal() {
+        vec![STDIN.to_string()]
+    } else {
+        opts.files.clone()
+    };
+    if files.is_empty() {
         return Err(eyre::eyre!("No YAML file specified"));
     }
 
-    let schema_filename = opts.schemas.first().unwrap();
-    let root_schema = RootSchema::load_file(schema_filename)
-        .wrap_err_with(|| format!("Failed to read YAML schema file: {}", schema_filename))?;
+    let loader_options = LoaderOptions {
+        unknown_keywords: if opts.strict_schema {
+            UnknownKeywords::Strict
+        } else {
+            UnknownKeywords::Warn
+        },
+        ..Default::default()
+    };
+    let root_schemas = load_schemas(&opts.schemas, opts.schema_mode, &loader_options)?;
 
-    let yaml_filename = opts.file.as_ref().unwrap();
-    let yaml_contents = std::fs::read_to_string(yaml_filename)
-        .wrap_err_with(|| format!("Failed to read YAML file: {}", yaml_filename))?;
+    let yaml_filenames = expand_paths(&files)?;
+    if opts.output.is_some() && yaml_filenames.len() > 1 {
+        return Err(eyre::eyre!(
+            "--output can only be used with a single YAML file, use --format to report on many"
+        ));
+    }
 
-    match Engine::evaluate(&root_schema, &yaml_contents, opts.fail_fast) {
-        Ok(context) => {
-            if context.has_errors() {
-                for error in context.errors.borrow().iter() {
-                    eprintln!("{}", error);
+    let options = EngineOptions {
+        fail_fast: opts.fail_fast,
+        all_documents: opts.all_documents,
+        warn_deprecated: opts.warn_deprecated,
+        ..Default::default()
+    };
+    let mut report = Report::new();
+    for yaml_filename in &yaml_filenames {
+        let (yaml_filename, contents) = if yaml_filename == STDIN {
+            (
+                &opts.stdin_filename,
+                std::io::read_to_string(std::io::stdin()),
+            )
+        } else {
+            (yaml_filename, std::fs::read_to_string(yaml_filename))
+        };
+        let result = contents
+            .wrap_err("Failed to read YAML file")
+            .and_then(|yaml_contents| {
+                let mut errors = Vec::new();
+                for root_schema in &root_schemas {
+                    let context =
+                        Engine::evaluate_with_options(root_schema, &yaml_contents, &options)?;
+                    for warning in context.warnings.take() {
+                        eprintln!("{}: warning: {}", yaml_filename, warning);
+                    }
+                    errors.extend(context.errors.take());
+                    if opts.fail_fast && !errors.is_empty() {
+                        break;
+                    }
                 }
-                return Ok(1);
+                Ok((yaml_contents, errors))
+            });
+        match result {
+            Ok((yaml_contents, errors)) => {
+                if let Some(format) = opts.output {
+                    println!(
+                        "{}",
+                        OutputUnit::from_errors(&errors, format.into()).to_json()
+                    );
+                    return Ok(if errors.is_empty() { 0 } else { 1 });
+                }
+                report.add_file_with_source(yaml_filename, yaml_contents, errors);
             }
-            Ok(0)
+            // Files that can't be read or parsed are reported as failing, like invalid files
+            Err(e) => report.add_file_error(yaml_filename, error_chain(&e)),
         }
-        Err(e) => {
-            eprintln!("Validation failed: {}", e);
-            Ok(1)
+        if opts.fail_fast && report.has_errors() {
+            break;
         }
     }
+
+    print_report(&report, opts.format.into());
+    let failed = report
+        .files
+        .iter()
+        .filter(|file| !file.errors.is_empty())
+        .count();
+    if yaml_filenames.len() > 1 && matches!(opts.format, FormatArg::Text | FormatArg::Pretty) {
+        eprintln!(
+            "Validated {} files: {} passed, {} failed",
+            yaml_filenames.len(),
+            report.files.len() - failed,
+            failed
+        );
+    }
+    Ok(if failed > 0 { 1 } else { 0 })
+}
+
+/// Format an error with its causes, skipping the causes that are already part of the message,
+/// e.g. the `ScanError` in `YAML parsing error: {0}`
+fn error_chain(e: &eyre::Report) -> String {
+    let mut message = String::new();
+    for cause in e.chain().map(|cause| cause.to_string()) {
+        if message.contains(&cause) {
+            continue;
+        }
+        if !message.is_empty() {
+            message.push_str(": ");
+        }
+        message.push_str(&cause);
+    }
+    message
+}
+
+/// Print the report in the given format. Text is written to stderr, other formats to stdout.
+fn print_report(report: &Report, format: ReportFormat) {
+    match format {
+        ReportFormat::Text => {
+            if report.has_errors() {
+                eprintln!("{}", report.render(ReportFormat::Text));
+            }
+        }
+        ReportFormat::Pretty => {
+            if report.has_errors() {
+                let color =
+                    std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+                println!("{}", report.render_pretty(color));
+            }
+        }
+        format => println!("{}", report.render(format)),
+    }
+}
+
+/// Load the schemas to validate against. In `all-of` mode that's every schema, and in `refs`
+/// mode it's only the first, with the others registered as `$ref` targets by their `$id`.
+fn load_schemas(
+    schema_filenames: &[String],
+    schema_mode: SchemaMode,
+    options: &LoaderOptions,
+) -> Result<Vec<RootSchema>> {
+    let load_error =
+        |schema_filename: &str| format!("Failed to read YAML schema file: {}", schema_filename);
+    let root_schemas = match schema_mode {
+        SchemaMode::AllOf => schema_filenames
+            .iter()
+            .map(|schema_filename| {
+                RootSchema::load_file_with_options(schema_filename, options)
+                    .wrap_err_with(|| load_error(schema_filename))
+            })
+            .collect::<Result<Vec<RootSchema>>>()?,
+        SchemaMode::Refs => {
+            let file_system_resolver = FileSystemResolver::default();
+            let mut resolver = IdResolver::new(&file_system_resolver);
+            for schema_filename in &schema_filenames[1..] {
+                resolver
+                    .register_file(schema_filename)
+                    .wrap_err_with(|| load_error(schema_filename))?;
+            }
+            let schema_filename = &schema_filenames[0];
+            let root_schema =
+                loader::load_file_with_resolver(schema_filename.as_str(), &resolver, options)
+                    .wrap_err_with(|| load_error(schema_filename))?;
+            vec![root_schema]
+        }
+    };
+    for (schema_filename, root_schema) in schema_filenames.iter().zip(&root_schemas) {
+        print_schema_warnings(schema_filename, root_schema);
+    }
+    Ok(root_schemas)
+}
+
+/// Print the warnings from loading a schema, and the schemas it references, to stderr
+fn print_schema_warnings(schema_filename: &str, root_schema: &RootSchema) {
+    for warning in &root_schema.warnings {
+        eprintln!("{}: warning: {}", schema_filename, warning);
+    }
+    let mut external_schemas: Vec<&RootSchema> = root_schema.external_schemas.values().collect();
+    external_schemas.sort_by_key(|schema| schema.location.clone());
+    for external_schema in external_schemas {
+        for warning in &external_schema.warnings {
+            eprintln!(
+                "{}: warning: {}",
+                external_schema.location.as_deref().unwrap_or_default(),
+                warning
+            );
+        }
+    }
+}
+
+/// Expand the paths given on the command line into the YAML files to validate, in order and
+/// without duplicates. Directories are walked recursively for `*.yaml` and `*.yml` files, and
+/// glob patterns are matched against the files under the directory they start with.
+fn expand_paths(paths: &[String]) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path == STDIN || Path::new(path).is_file() {
+            files.push(path.clone());
+        } else if Path::new(path).is_dir() {
+            for entry in WalkDir::new(path).sort_by_file_name() {
+                let entry = entry?;
+                let is_yaml = entry
+                    .path()
+                    .extension()
+                    .is_some_and(|extension| extension == "yaml" || extension == "yml");
+                if entry.file_type().is_file() && is_yaml {
+                    files.push(entry.path().display().to_string());
+                }
+            }
+        } else if path.contains(['*', '?', '[', '{']) {
+            files.extend(expand_glob(path)?);
+        } else {
+            return Err(eyre::eyre!("No such file or directory: {}", path));
+        }
+    }
+    let mut seen = HashSet::new();
+    files.retain(|file| seen.insert(file.clone()));
+    Ok(files)
+}
+
+/// Returns the files that match a glob pattern, e.g. `k8s/**/*.yaml`
+fn expand_glob(pattern: &str) -> Result<Vec<String>> {
+    // The walked paths don't start with `./`, so neither can the pattern they're matched against
+    let mut pattern = pattern;
+    while let Some(rest) = pattern.strip_prefix("./") {
+        pattern = rest;
+    }
+    let matcher = globset::GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .wrap_err_with(|| format!("Invalid glob pattern: {}", pattern))?
+        .compile_matcher();
+    // Only walk the directory that the pattern starts with
+    let base: Vec<&str> = pattern
+        .split('/')
+        .take_while(|segment| !segment.contains(['*', '?', '[', '{']))
+        .collect();
+    let base = if base.is_empty() {
+        ".".to_string()
+    } else {
+        base.join("/")
+    };
+    let mut files = Vec::new();
+    for entry in WalkDir::new(&base).sort_by_file_name() {
+        let entry = entry?;
+        let path = entry.path().display().to_string();
+        let path = path.strip_prefix("./").unwrap_or(&path).to_string();
+        if entry.file_type().is_file() && matcher.is_match(&path) {
+            files.push(path);
+        }
+    }
+    if files.is_empty() {
+        return Err(eyre::eyre!("No files match: {}", pattern));
+    }
+    Ok(files)
 }
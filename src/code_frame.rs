@@ -0,0 +1,277 @@
+/// Code frames, which show the lines of a YAML file that caused a validation error, with the
+/// failing value underlined
+use crate::validation::LineCol;
+use crate::validation::ValidationError;
+
+/// The most lines of a value to show, e.g. for a long block scalar
+const MAX_LINES: usize = 5;
+
+const RED: &str = "1;31";
+const BLUE: &str = "1;34";
+const BOLD: &str = "1";
+
+/// Render a validation error as a code frame, e.g.
+///
+/// ```text
+/// error[type]: Expected a string, but got: Integer(42)
+///  --> config.yaml:1:6
+///   |
+/// 1 | foo: 42
+///   |      ^^
+///   = note: the `type` keyword failed at /properties/foo/type, for the value at .foo
+/// ```
+///
+/// If `color` is true, the frame is coloured with ANSI escape codes.
+pub fn render_code_frame(
+    file: &str,
+    source: Option<&str>,
+    error: &ValidationError,
+    color: bool,
+) -> String {
+    let paint = |code: &str, s: &str| -> String {
+        if color {
+            format!("\x1b[{}m{}\x1b[0m", code, s)
+        } else {
+            s.to_string()
+        }
+    };
+    let keyword = error.kind.keyword();
+    let mut out = match keyword {
+        Some(keyword) => paint(RED, &format!("error[{}]", keyword)),
+        None => paint(RED, "error"),
+    };
+    out.push_str(&paint(BOLD, &format!(": {}", error.error)));
+
+    let mut lines = match (source, &error.line_col) {
+        (Some(source), Some(start)) => {
+            let end = error.end_line_col.as_ref().unwrap_or(start);
+            underlined_lines(source, start, end)
+        }
+        _ => vec![],
+    };
+    // Only the first lines of a long value are shown, followed by "..."
+    let is_truncated = lines.len() > MAX_LINES;
+    lines.truncate(MAX_LINES);
+    let gutter_width = lines
+        .last()
+        .map(|line| line.number.to_string().len())
+        .unwrap_or(1);
+    let gutter = " ".repeat(gutter_width);
+
+    match &error.line_col {
+        Some(line_col) => out.push_str(&format!(
+            "\n{}{} {}:{}:{}",
+            gutter,
+            paint(BLUE, "-->"),
+            file,
+            line_col.line,
+            line_col.col
+        )),
+        None => out.push_str(&format!("\n{}{} {}", gutter, paint(BLUE, "-->"), file)),
+    }
+    if !lines.is_empty() {
+        out.push_str(&format!("\n{} {}", gutter, paint(BLUE, "|")));
+    }
+    for line in &lines {
+        out.push_str(&format!(
+            "\n{} {} {}",
+            paint(
+                BLUE,
+                &format!("{:>width$}", line.number, width = gutter_width)
+            ),
+            paint(BLUE, "|"),
+            line.text
+        ));
+        if line.from < line.to {
+            // Keep tabs in the indentation, so that the underline lines up with the text
+            let indent: String = line
+                .text
+                .chars()
+                .take(line.from)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            out.push_str(&format!(
+                "\n{} {} {}{}",
+                gutter,
+                paint(BLUE, "|"),
+                indent,
+                paint(RED, &"^".repeat(line.to - line.from))
+            ));
+        }
+    }
+    if is_truncated {
+        out.push_str(&format!("\n{} {}", gutter, paint(BLUE, "...")));
+    }
+
+    let path = format!(".{}", error.path);
+    match keyword {
+        Some(keyword) => out.push_str(&format!(
+            "\n{} {} the `{}` keyword failed at {}, for the value at {}",
+            gutter,
+            paint(BLUE, "= note:"),
+            keyword,
+            error.keyword_location,
+            path
+        )),
+        None if !error.keyword_location.is_empty() => out.push_str(&format!(
+            "\n{} {} the schema at {} failed, for the value at {}",
+            gutter,
+            paint(BLUE, "= note:"),
+            error.keyword_location,
+            path
+        )),
+        None => (),
+    }
+    out
+}
+
+/// A line of source, with the columns to underline
+struct UnderlinedLine<'s> {
+    number: usize,
+    text: &'s str,
+    /// The first column to underline, 0-indexed
+    from: usize,
+    /// The column after the last one to underline, 0-indexed
+    to: usize,
+}
+
+/// Returns the lines from `start` to `end`, with the span between them underlined. Indentation
+/// and trailing whitespace are never underlined, and an empty span (such as a collection's) is
+/// underlined with a single caret.
+fn underlined_lines<'s>(
+    source: &'s str,
+    start: &LineCol,
+    end: &LineCol,
+) -> Vec<UnderlinedLine<'s>> {
+    let mut lines: Vec<UnderlinedLine> = source
+        .lines()
+        .enumerate()
+        .skip(start.line.saturating_sub(1))
+        .take_while(|(index, _)| *index < end.line.max(start.line))
+        .map(|(index, text)| {
+            let number = index + 1;
+            let content_start = text.chars().count() - text.trim_start().chars().count();
+            let content_end = text.trim_end().chars().count();
+            let from = if number == start.line {
+                start.col - 1
+            } else {
+                content_start
+            };
+            let to = if number == end.line {
+                (end.col - 1).min(content_end)
+            } else {
+                content_end
+            };
+            UnderlinedLine {
+                number,
+                text,
+                from,
+                to,
+            }
+        })
+        .collect();
+    // A span that ends at the start of a line doesn't include that line
+    while lines.len() > 1 && lines.last().is_some_and(|line| line.from >= line.to) {
+        lines.pop();
+    }
+    if let [line] = lines.as_mut_slice() {
+        if line.from >= line.to {
+            line.to = line.from + 1;
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::RootSchema;
+
+    use super::*;
+
+    fn errors(schema: &str, value: &str) -> Vec<ValidationError> {
+        let root_schema = RootSchema::load_from_str(schema).unwrap();
+        let context = Engine::evaluate(&root_schema, value, false).unwrap();
+        context.errors.take()
+    }
+
+    #[test]
+    fn test_render_code_frame() {
+        let source = "name: test\nfoo: 42\n";
+        let errors = errors(
+            "type: object\nproperties:\n  foo:\n    type: string\n",
+            source,
+        );
+        let expected = r#"error[type]: Expected a string, but got: Integer(42)
+ --> config.yaml:2:6
+  |
+2 | foo: 42
+  |      ^^
+  = note: the `type` keyword failed at /properties/foo/type, for the value at .foo"#;
+        assert_eq!(
+            render_code_frame("config.yaml", Some(source), &errors[0], false),
+            expected
+        );
+        let colored = render_code_frame("config.yaml", Some(source), &errors[0], true);
+        assert!(colored.starts_with("\x1b[1;31merror[type]\x1b[0m"));
+    }
+
+    #[test]
+    fn test_render_code_frame_for_multiline_values() {
+        let source = "text: |\n  first\n  second\nnext: 1\n";
+        let errors = errors(
+            "type: object\nproperties:\n  text:\n    type: string\n    maxLength: 3\n",
+            source,
+        );
+        let expected = r#"error[maxLength]: String is too long! (max length: 3)
+ --> config.yaml:2:3
+  |
+2 |   first
+  |   ^^^^^
+3 |   second
+  |   ^^^^^^
+  = note: the `maxLength` keyword failed at /properties/text/maxLength, for the value at .text"#;
+        assert_eq!(
+            render_code_frame("config.yaml", Some(source), &errors[0], false),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_render_code_frame_for_long_values() {
+        let schema = "type: object\nproperties:\n  text:\n    type: string\n    maxLength: 3\n";
+        let source = "text: |\n  1\n  2\n  3\n  4\n  5\n";
+        let frame = render_code_frame(
+            "config.yaml",
+            Some(source),
+            &errors(schema, source)[0],
+            false,
+        );
+        assert!(frame.contains("6 |   5\n  |   ^\n  = note:"), "{}", frame);
+
+        let source = "text: |\n  1\n  2\n  3\n  4\n  5\n  6\n";
+        let frame = render_code_frame(
+            "config.yaml",
+            Some(source),
+            &errors(schema, source)[0],
+            false,
+        );
+        assert!(
+            frame.contains("6 |   5\n  |   ^\n  ...\n  = note:"),
+            "{}",
+            frame
+        );
+        assert!(!frame.contains("7 |"), "{}", frame);
+    }
+
+    #[test]
+    fn test_render_code_frame_for_collections() {
+        let source = "items:\n  - a\n";
+        let errors = errors(
+            "type: object\nproperties:\n  items:\n    type: array\n    minItems: 2\n",
+            source,
+        );
+        let frame = render_code_frame("config.yaml", Some(source), &errors[0], false);
+        assert!(frame.contains("2 |   - a\n  |   ^\n"), "{}", frame);
+    }
+}
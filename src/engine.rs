@@ -1,20 +1,53 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::validation::formats::FormatRegistry;
+use crate::validation::formats::UnknownFormat;
 use crate::validation::Context;
+use crate::validation::ValidationError;
+use crate::validation::ValidationErrorKind;
 use crate::Error;
 use crate::Result;
 use crate::RootSchema;
 use crate::YamlSchema;
 
+/// Options that control how the Engine evaluates a YAML document
+#[derive(Debug, Default)]
+pub struct EngineOptions {
+    /// Stop at the first validation error
+    pub fail_fast: bool,
+    /// Treat the `format` keyword as an assertion, rather than as an annotation only.
+    /// JSON Schema 2020-12 specifies annotation-only behaviour by default.
+    pub format_assertion: bool,
+    /// Custom format validators, consulted for formats that aren't built-in
+    pub formats: FormatRegistry,
+    /// What to do when asserting a format that is neither built-in nor registered
+    pub unknown_format: UnknownFormat,
+    /// Validate every document in a multi-document YAML stream, rather than only the first
+    pub all_documents: bool,
+    /// Warn when a value is at a location the schema marks as `deprecated`
+    pub warn_deprecated: bool,
+}
+
+impl EngineOptions {
+    /// Register a custom validator for the named format, e.g. `semver`
+    pub fn register_format<N, F>(&mut self, name: N, validator: F)
+    where
+        N: Into<String>,
+        F: Fn(&str) -> std::result::Result<(), String> + 'static,
+    {
+        self.formats.register(name, validator);
+    }
+}
+
 #[derive(Debug)]
 pub struct Engine<'a> {
     pub root_schema: &'a RootSchema,
-    pub context: Rc<RefCell<Context>>,
+    pub context: Rc<RefCell<Context<'a>>>,
 }
 
 impl<'a> Engine<'a> {
-    pub fn new(root_schema: &'a RootSchema, context: Context) -> Self {
+    pub fn new(root_schema: &'a RootSchema, context: Context<'a>) -> Self {
         Engine {
             root_schema,
             context: Rc::new(RefCell::new(context)),
@@ -25,37 +58,98 @@ impl<'a> Engine<'a> {
         root_schema: &'a RootSchema,
         value: &str,
         fail_fast: bool,
-    ) -> Result<Context> {
-        let context = Context {
-            current_schema: Some(root_schema.schema.clone()),
+    ) -> Result<Context<'a>> {
+        let options = EngineOptions {
             fail_fast,
             ..Default::default()
         };
+        Engine::evaluate_with_options(root_schema, value, &options)
+    }
+
+    pub fn evaluate_with_options(
+        root_schema: &'a RootSchema,
+        value: &str,
+        options: &EngineOptions,
+    ) -> Result<Context<'a>> {
+        let context = Engine::context_with_options(root_schema, options);
         let engine = Engine::new(root_schema, context);
         let docs = saphyr::MarkedYaml::load_from_str(value).map_err(Error::YamlParsingError)?;
         if docs.is_empty() {
-            match root_schema.schema.as_ref() {
+            match root_schema.schema.without_annotations() {
                 YamlSchema::Empty => (),
                 YamlSchema::BooleanLiteral(false) => {
-                    engine
-                        .context
-                        .borrow_mut()
-                        .add_doc_error("Empty YAML document is not allowed");
+                    engine.context.borrow_mut().add_doc_error(
+                        ValidationErrorKind::EmptyDocument,
+                        "Empty YAML document is not allowed",
+                    );
                 }
                 YamlSchema::BooleanLiteral(true) => (),
-                _ => engine
-                    .context
-                    .borrow_mut()
-                    .add_doc_error("Empty YAML document is not allowed"),
+                _ => engine.context.borrow_mut().add_doc_error(
+                    ValidationErrorKind::EmptyDocument,
+                    "Empty YAML document is not allowed",
+                ),
             }
         } else {
-            let yaml = docs.first().unwrap();
-            engine
-                .root_schema
-                .validate(&engine.context.borrow(), yaml)?;
+            let count = if options.all_documents { docs.len() } else { 1 };
+            for (index, yaml) in docs.iter().enumerate().take(count) {
+                engine.context.borrow_mut().document_index = index;
+                match engine.root_schema.validate(&engine.context.borrow(), yaml) {
+                    Ok(()) => (),
+                    // Stop at the first error, and return the errors found so far
+                    Err(Error::FailFast) => break,
+                    Err(e) => return Err(e),
+                }
+            }
         }
         Ok(engine.context.take())
     }
+
+    /// Validate every document in a multi-document YAML stream against the schema chosen for it
+    /// by `schema_for`, which is given the index of the document and the document itself.
+    /// Documents that `schema_for` returns None for are not validated.
+    ///
+    /// `options.all_documents` is ignored, since every document is always considered.
+    pub fn evaluate_documents<'s, F>(
+        value: &str,
+        options: &EngineOptions,
+        mut schema_for: F,
+    ) -> Result<Vec<ValidationError>>
+    where
+        F: FnMut(usize, &saphyr::MarkedYaml) -> Option<&'s RootSchema>,
+    {
+        let docs = saphyr::MarkedYaml::load_from_str(value).map_err(Error::YamlParsingError)?;
+        let mut errors = Vec::new();
+        for (index, yaml) in docs.iter().enumerate() {
+            let Some(root_schema) = schema_for(index, yaml) else {
+                continue;
+            };
+            let mut context = Engine::context_with_options(root_schema, options);
+            context.document_index = index;
+            let result = root_schema.validate(&context, yaml);
+            errors.extend(context.errors.take());
+            match result {
+                Ok(()) => (),
+                Err(Error::FailFast) => break,
+                Err(e) => return Err(e),
+            }
+            if options.fail_fast && !errors.is_empty() {
+                break;
+            }
+        }
+        Ok(errors)
+    }
+
+    fn context_with_options<'r>(
+        root_schema: &'r RootSchema,
+        options: &EngineOptions,
+    ) -> Context<'r> {
+        let mut context = Context::with_root_schema(root_schema, options.fail_fast);
+        context.format_assertion = options.format_assertion;
+        context.formats = Rc::new(options.formats.clone());
+        context.unknown_format = options.unknown_format;
+        context.warn_deprecated = options.warn_deprecated;
+        context
+    }
 }
 
 #[cfg(test)]
@@ -82,4 +176,62 @@ mod tests {
         let context = Engine::evaluate(&root_schema, "", false).unwrap();
         assert!(context.has_errors());
     }
+
+    const STREAM: &str = "name: first\n---\nname: 2\n---\nname: 3\n";
+
+    #[test]
+    fn test_engine_fail_fast() {
+        let root_schema = RootSchema::load_from_str("type: string").unwrap();
+        let context = Engine::evaluate(&root_schema, "42", true).unwrap();
+        assert_eq!(context.errors.borrow().len(), 1);
+    }
+
+    #[test]
+    fn test_engine_all_documents() {
+        let root_schema =
+            RootSchema::load_from_str("type: object\nproperties:\n  name:\n    type: string\n")
+                .unwrap();
+        let context = Engine::evaluate(&root_schema, STREAM, false).unwrap();
+        assert!(!context.has_errors());
+
+        let options = EngineOptions {
+            all_documents: true,
+            ..Default::default()
+        };
+        let context = Engine::evaluate_with_options(&root_schema, STREAM, &options).unwrap();
+        let errors = context.errors.borrow();
+        assert_eq!(
+            errors
+                .iter()
+                .map(|error| error.document_index)
+                .collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(errors[0].line_col.as_ref().unwrap().line, 3);
+    }
+
+    #[test]
+    fn test_engine_evaluate_documents() {
+        let string_names =
+            RootSchema::load_from_str("type: object\nproperties:\n  name:\n    type: string\n")
+                .unwrap();
+        let integer_names =
+            RootSchema::load_from_str("type: object\nproperties:\n  name:\n    type: integer\n")
+                .unwrap();
+        let errors =
+            Engine::evaluate_documents(STREAM, &EngineOptions::default(), |index, _| match index {
+                0 => Some(&string_names),
+                1 => Some(&integer_names),
+                _ => None,
+            })
+            .unwrap();
+        assert!(errors.is_empty());
+
+        let errors = Engine::evaluate_documents(STREAM, &EngineOptions::default(), |_, _| {
+            Some(&string_names)
+        })
+        .unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[1].document_index, 2);
+    }
 }
@@ -1,5 +1,7 @@
 use thiserror::Error;
 
+use crate::validation::LineCol;
+
 /// Unexpected errors that can occur during the validation of a YAML schema
 #[derive(Debug, Error)]
 pub enum Error {
@@ -17,12 +19,55 @@ pub enum Error {
     RegexParsingError(#[from] regex::Error),
     #[error("Unsupported type '{0}'!")]
     UnsupportedType(String),
+    #[error("Circular reference detected: {0}")]
+    CircularReference(String),
     #[error("Generic YAML schema error: {0}")]
     GenericError(String),
+    /// The schema itself is invalid, at the given line and column of the schema file. The
+    /// location of the schema file is only set for schemas that were loaded by a `$ref`.
+    #[error("{}[{line}:{col}] {message}", .location.as_ref().map(|location| format!("{}: ", location)).unwrap_or_default())]
+    InvalidSchema {
+        location: Option<String>,
+        line: usize,
+        col: usize,
+        message: String,
+    },
     #[error("Fail fast signal")]
     FailFast,
 }
 
+impl Error {
+    /// An InvalidSchema error at the position of the given schema value
+    pub fn invalid_schema(value: &saphyr::MarkedYaml, message: String) -> Error {
+        let line_col = LineCol::from(value);
+        Error::InvalidSchema {
+            location: None,
+            line: line_col.line,
+            col: line_col.col,
+            message,
+        }
+    }
+
+    /// Set the location of the schema file that an InvalidSchema error was found in, if it
+    /// isn't already set. Other errors are returned as they are.
+    pub fn in_schema(self, schema_location: &str) -> Error {
+        match self {
+            Error::InvalidSchema {
+                location: None,
+                line,
+                col,
+                message,
+            } => Error::InvalidSchema {
+                location: Some(schema_location.to_string()),
+                line,
+                col,
+                message,
+            },
+            e => e,
+        }
+    }
+}
+
 #[macro_export]
 macro_rules! fail_fast {
     ($context:expr) => {
@@ -51,3 +96,13 @@ macro_rules! generic_error {
         $crate::Error::GenericError($s.to_string())
     };
 }
+
+#[macro_export]
+macro_rules! schema_error {
+    ($value:expr, $s:literal, $($e:expr),+) => {
+        $crate::Error::invalid_schema($value, format!($s, $($e),+))
+    };
+    ($value:expr, $s:literal) => {
+        $crate::Error::invalid_schema($value, $s.to_string())
+    };
+}
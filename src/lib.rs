@@ -1,25 +1,51 @@
+use std::collections::HashMap;
 use std::rc::Rc;
 
+pub mod code_frame;
 pub mod engine;
 #[macro_use]
 pub mod error;
 pub mod loader;
+pub mod meta_schema;
+pub mod output;
+pub mod report;
+pub mod resolver;
 pub mod schemas;
 pub mod validation;
 
 pub use engine::Engine;
+pub use engine::EngineOptions;
 pub use error::Error;
+pub use loader::LoaderOptions;
+pub use loader::LoaderWarning;
+pub use loader::UnknownKeywords;
+pub use meta_schema::check_schema;
+pub use output::OutputFormat;
+pub use output::OutputUnit;
+pub use report::Report;
+pub use report::ReportFormat;
+pub use resolver::FileSystemResolver;
+pub use resolver::IdResolver;
+pub use resolver::SchemaResolver;
+pub use schemas::AllOfSchema;
+pub use schemas::AnnotatedSchema;
+pub use schemas::Annotations;
 pub use schemas::AnyOfSchema;
 pub use schemas::ArraySchema;
 pub use schemas::BoolOrTypedSchema;
 pub use schemas::ConstSchema;
 pub use schemas::EnumSchema;
+pub use schemas::IfThenElseSchema;
 pub use schemas::IntegerSchema;
+pub use schemas::MultipleTypesSchema;
 pub use schemas::NotSchema;
 pub use schemas::NumberSchema;
 pub use schemas::ObjectSchema;
 pub use schemas::OneOfSchema;
+pub use schemas::RefSchema;
 pub use schemas::StringSchema;
+pub use validation::formats::FormatRegistry;
+pub use validation::formats::UnknownFormat;
 pub use validation::Context;
 pub use validation::Validator;
 
@@ -38,7 +64,15 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct RootSchema {
     pub id: Option<String>,
     pub meta_schema: Option<String>,
+    /// Where the schema was loaded from, as resolved by the SchemaResolver
+    pub location: Option<String>,
+    pub defs: Option<HashMap<String, YamlSchema>>,
+    /// The external schema documents referenced by `$ref`, keyed by their location
+    pub external_schemas: HashMap<String, RootSchema>,
     pub schema: Rc<YamlSchema>,
+    /// Problems found while loading the schema that didn't stop it from loading, such as
+    /// unknown keywords with `UnknownKeywords::Warn`
+    pub warnings: Vec<LoaderWarning>,
 }
 
 impl RootSchema {
@@ -47,7 +81,11 @@ impl RootSchema {
         RootSchema {
             id: None,
             meta_schema: None,
+            location: None,
+            defs: None,
+            external_schemas: HashMap::new(),
             schema: Rc::new(schema),
+            warnings: Vec::new(),
         }
     }
 
@@ -56,18 +94,70 @@ impl RootSchema {
         loader::load_file(path)
     }
 
+    /// Load a RootSchema from a file, with the given LoaderOptions
+    pub fn load_file_with_options(path: &str, options: &LoaderOptions) -> Result<RootSchema> {
+        loader::load_file_with_options(path, options)
+    }
+
+    /// Load a RootSchema from a file, using the given SchemaResolver to load referenced schemas
+    pub fn load_file_with_resolver(
+        path: &str,
+        resolver: &dyn SchemaResolver,
+    ) -> Result<RootSchema> {
+        loader::load_file_with_resolver(path, resolver, &LoaderOptions::default())
+    }
+
     pub fn load_from_str(schema: &str) -> Result<RootSchema> {
-        let docs = saphyr::Yaml::load_from_str(schema)?;
-        if docs.is_empty() {
-            return Ok(RootSchema::new(YamlSchema::Empty)); // empty schema
-        }
-        loader::load_from_doc(docs.first().unwrap())
+        loader::load_from_str(schema)
+    }
+
+    /// Load a RootSchema from a string, with the given LoaderOptions
+    pub fn load_from_str_with_options(schema: &str, options: &LoaderOptions) -> Result<RootSchema> {
+        loader::load_from_str_with_options(schema, options)
     }
 
     pub fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
         self.schema.validate(context, value)?;
         Ok(())
     }
+
+    /// Resolve a `$ref`, such as `#/$defs/address` or `/schemas/common.yaml#/$defs/port`
+    ///
+    /// External references must have been loaded into `external_schemas` by the loader
+    pub fn resolve(&self, reference: &str) -> Option<&YamlSchema> {
+        let (uri, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+        if uri.is_empty()
+            || Some(uri) == self.location.as_deref()
+            || Some(uri) == self.id.as_deref()
+        {
+            self.resolve_fragment(fragment)
+        } else {
+            self.external_schemas.get(uri)?.resolve_fragment(fragment)
+        }
+    }
+
+    /// Resolve a JSON Pointer fragment within this document
+    ///
+    /// Only the empty fragment (the root schema itself), `/$defs/<name>` and `/definitions/<name>`
+    /// are supported
+    fn resolve_fragment(&self, fragment: &str) -> Option<&YamlSchema> {
+        if fragment.is_empty() {
+            return Some(self.schema.as_ref());
+        }
+        let mut segments = fragment.strip_prefix('/')?.split('/');
+        match (segments.next(), segments.next(), segments.next()) {
+            (Some("$defs"), Some(name), None) | (Some("definitions"), Some(name), None) => {
+                let name = unescape_json_pointer(name);
+                self.defs.as_ref().and_then(|defs| defs.get(&name))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Unescape a JSON Pointer reference token, where `~1` is `/` and `~0` is `~`
+fn unescape_json_pointer(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
 }
 
 /// A Number is either an integer or a float
@@ -98,7 +188,7 @@ impl std::fmt::Display for Number {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum ConstValue {
     Boolean(bool),
     Null,
@@ -122,15 +212,15 @@ impl ConstValue {
     pub fn string<V: Into<String>>(value: V) -> ConstValue {
         ConstValue::String(value.into())
     }
-    pub fn from_saphyr_yaml(value: &saphyr::Yaml) -> ConstValue {
-        match value {
-            saphyr::Yaml::Boolean(b) => ConstValue::Boolean(*b),
-            saphyr::Yaml::Integer(i) => ConstValue::Number(Number::integer(*i)),
-            saphyr::Yaml::Real(s) => ConstValue::Number(Number::float(s.parse::<f64>().unwrap())),
-            saphyr::Yaml::String(s) => ConstValue::String(s.clone()),
-            saphyr::Yaml::Null => ConstValue::Null,
-            _ => panic!("Expected a constant value, but got: {:?}", value),
-        }
+    /// Load a constant value from a schema, e.g. the value of `const` or an item of `enum`
+    pub fn from_saphyr_yaml(value: &saphyr::MarkedYaml) -> Result<ConstValue> {
+        ConstValue::try_from(&value.data).map_err(|_| {
+            schema_error!(
+                value,
+                "Expected a constant value, but got: {:?}",
+                value.data
+            )
+        })
     }
 }
 
@@ -189,28 +279,44 @@ impl std::fmt::Display for ConstValue {
 
 /// YamlSchema is the core of the validation model
 #[derive(Debug, Default, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum YamlSchema {
     #[default]
     Empty, // no value
-    BooleanLiteral(bool),   // `true` or `false`
-    Const(ConstSchema),     // `const`
-    TypeNull,               // `type: null`
-    Array(ArraySchema),     // `type: array`
-    BooleanSchema,          // `type: boolean`
-    Integer(IntegerSchema), // `type: integer`
-    Number(NumberSchema),   // `type: number`
-    Object(ObjectSchema),   // `type: object`
-    String(StringSchema),   // `type: string`
-    Enum(EnumSchema),       // `enum`
-    AnyOf(AnyOfSchema),     // `anyOf`
-    OneOf(OneOfSchema),     // `oneOf`
-    Not(NotSchema),         // `not`
+    BooleanLiteral(bool),               // `true` or `false`
+    Const(ConstSchema),                 // `const`
+    TypeNull,                           // `type: null`
+    Array(ArraySchema),                 // `type: array`
+    BooleanSchema,                      // `type: boolean`
+    Integer(IntegerSchema),             // `type: integer`
+    Number(NumberSchema),               // `type: number`
+    Object(ObjectSchema),               // `type: object`
+    String(StringSchema),               // `type: string`
+    MultipleTypes(MultipleTypesSchema), // e.g. `type: [string, null]`
+    Enum(EnumSchema),                   // `enum`
+    AllOf(AllOfSchema),                 // `allOf`
+    AnyOf(AnyOfSchema),                 // `anyOf`
+    OneOf(OneOfSchema),                 // `oneOf`
+    Not(NotSchema),                     // `not`
+    IfThenElse(IfThenElseSchema),       // `if`, `then` and `else`
+    Ref(RefSchema),                     // `$ref`
+    Annotated(AnnotatedSchema),         // e.g. `title` or `description` alongside a schema
 }
 
 impl YamlSchema {
     pub fn boolean_literal(value: bool) -> YamlSchema {
         YamlSchema::BooleanLiteral(value)
     }
+
+    /// Returns the schema without its annotations, which don't affect validation
+    pub fn without_annotations(&self) -> &YamlSchema {
+        match self {
+            YamlSchema::Annotated(annotated_schema) => {
+                annotated_schema.schema.without_annotations()
+            }
+            schema => schema,
+        }
+    }
 }
 
 impl std::fmt::Display for YamlSchema {
@@ -223,6 +329,9 @@ impl std::fmt::Display for YamlSchema {
             YamlSchema::Const(c) => write!(f, "{}", c),
             YamlSchema::Enum(e) => write!(f, "{}", e),
             YamlSchema::Integer(i) => write!(f, "{}", i),
+            YamlSchema::AllOf(all_of_schema) => {
+                write!(f, "{}", all_of_schema)
+            }
             YamlSchema::AnyOf(any_of_schema) => {
                 write!(f, "{}", any_of_schema)
             }
@@ -232,10 +341,18 @@ impl std::fmt::Display for YamlSchema {
             YamlSchema::Not(not_schema) => {
                 write!(f, "{}", not_schema)
             }
+            YamlSchema::IfThenElse(if_then_else_schema) => {
+                write!(f, "{}", if_then_else_schema)
+            }
+            YamlSchema::Ref(ref_schema) => {
+                write!(f, "{}", ref_schema)
+            }
             YamlSchema::String(s) => write!(f, "{}", s),
             YamlSchema::Number(n) => write!(f, "{}", n),
             YamlSchema::Object(o) => write!(f, "{}", o),
             YamlSchema::Array(a) => write!(f, "{}", a),
+            YamlSchema::MultipleTypes(m) => write!(f, "{}", m),
+            YamlSchema::Annotated(a) => write!(f, "{}", a),
         }
     }
 }
@@ -252,6 +369,9 @@ impl From<TypedSchema> for YamlSchema {
             TypedSchema::Number(number_schema) => YamlSchema::Number(number_schema),
             TypedSchema::Object(object_schema) => YamlSchema::Object(object_schema),
             TypedSchema::String(string_schema) => YamlSchema::String(string_schema),
+            TypedSchema::MultipleTypes(multiple_types_schema) => {
+                YamlSchema::MultipleTypes(multiple_types_schema)
+            }
         }
     }
 }
@@ -273,10 +393,58 @@ fn format_yaml_data(data: &saphyr::YamlData<saphyr::MarkedYaml>) -> String {
         saphyr::YamlData::Integer(i) => i.to_string(),
         saphyr::YamlData::Real(s) => s.clone(),
         saphyr::YamlData::String(s) => format!("\"{}\"", s),
+        // Collections are formatted in flow style, without the spans of their values
+        saphyr::YamlData::Array(array) => {
+            let items: Vec<String> = array.iter().map(|v| format_yaml_data(&v.data)).collect();
+            format!("[{}]", items.join(", "))
+        }
+        saphyr::YamlData::Hash(hash) => {
+            let entries: Vec<String> = hash
+                .iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{}: {}",
+                        format_yaml_data(&k.data),
+                        format_yaml_data(&v.data)
+                    )
+                })
+                .collect();
+            format!("{{{}}}", entries.join(", "))
+        }
         _ => format!("{:?}", data),
     }
 }
 
+/// Compares two YAML values for structural equality, as JSON Schema defines it: numbers are
+/// equal if they have the same value (so `1` equals `1.0`), mappings are equal if they have
+/// the same keys and values regardless of order, and sequences are equal item by item
+fn yaml_data_eq(
+    a: &saphyr::YamlData<saphyr::MarkedYaml>,
+    b: &saphyr::YamlData<saphyr::MarkedYaml>,
+) -> bool {
+    match (a, b) {
+        (saphyr::YamlData::Array(a), saphyr::YamlData::Array(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .zip(b.iter())
+                    .all(|(a, b)| yaml_data_eq(&a.data, &b.data))
+        }
+        (saphyr::YamlData::Hash(a), saphyr::YamlData::Hash(b)) => {
+            a.len() == b.len()
+                && a.iter().all(|(key, a_value)| {
+                    b.iter().any(|(other_key, b_value)| {
+                        yaml_data_eq(&key.data, &other_key.data)
+                            && yaml_data_eq(&a_value.data, &b_value.data)
+                    })
+                })
+        }
+        (saphyr::YamlData::Integer(i), saphyr::YamlData::Real(_)) => Some(*i as f64) == b.as_f64(),
+        (saphyr::YamlData::Real(_), saphyr::YamlData::Integer(i)) => a.as_f64() == Some(*i as f64),
+        (saphyr::YamlData::Real(_), saphyr::YamlData::Real(_)) => a.as_f64() == b.as_f64(),
+        _ => a == b,
+    }
+}
+
 fn format_marker(marker: &saphyr::Marker) -> String {
     format!("[{}, {}]", marker.line(), marker.col())
 }
@@ -307,4 +475,14 @@ mod tests {
         let s2 = ConstValue::string("NW");
         assert_eq!(s1, s2);
     }
+
+    #[test]
+    fn test_yaml_data_eq() {
+        let docs =
+            saphyr::MarkedYaml::load_from_str("[{a: 1, b: [x, 2.0]}, {b: [x, 2], a: 1.0}, {a: 1}]")
+                .unwrap();
+        let array = docs.first().unwrap().data.as_vec().unwrap();
+        assert!(yaml_data_eq(&array[0].data, &array[1].data));
+        assert!(!yaml_data_eq(&array[0].data, &array[2].data));
+    }
 }
@@ -1,7 +1,13 @@
 use std::collections::HashMap;
+use std::collections::VecDeque;
 use std::fs;
 use std::rc::Rc;
 
+use crate::schemas::TypeValue;
+use crate::validation::LineCol;
+use crate::AllOfSchema;
+use crate::AnnotatedSchema;
+use crate::Annotations;
 use crate::AnyOfSchema;
 use crate::ArraySchema;
 use crate::BoolOrTypedSchema;
@@ -9,55 +15,514 @@ use crate::ConstSchema;
 use crate::ConstValue;
 use crate::EnumSchema;
 use crate::Error;
+use crate::FileSystemResolver;
+use crate::IfThenElseSchema;
 use crate::IntegerSchema;
+use crate::MultipleTypesSchema;
 use crate::NotSchema;
 use crate::Number;
 use crate::NumberSchema;
 use crate::ObjectSchema;
 use crate::OneOfSchema;
+use crate::RefSchema;
 use crate::Result;
 use crate::RootSchema;
+use crate::SchemaResolver;
 use crate::StringSchema;
 use crate::TypedSchema;
 use crate::YamlSchema;
 
+/// A YAML hash in a schema document, with the position of every key and value
+pub type MarkedHash = saphyr::AnnotatedHash<saphyr::MarkedYaml>;
+
+/// What to do when a schema has a keyword that the loader doesn't know
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UnknownKeywords {
+    /// Unknown keywords are an error
+    #[default]
+    Strict,
+    /// Unknown keywords are ignored, with a warning for each
+    Warn,
+    /// Unknown keywords are ignored silently
+    Ignore,
+}
+
+/// Options that control how schemas are loaded
+#[derive(Debug)]
+pub struct LoaderOptions {
+    /// What to do with keywords that the loader doesn't know
+    pub unknown_keywords: UnknownKeywords,
+    /// Ignore extension keywords, which start with `x-` (e.g. `x-kubernetes-int-or-string`),
+    /// whatever `unknown_keywords` says
+    pub allow_extensions: bool,
+}
+
+impl Default for LoaderOptions {
+    fn default() -> Self {
+        LoaderOptions {
+            unknown_keywords: UnknownKeywords::default(),
+            allow_extensions: true,
+        }
+    }
+}
+
+/// A problem with a schema that doesn't stop it from loading, e.g. an unknown keyword
+#[derive(Debug, PartialEq)]
+pub struct LoaderWarning {
+    pub line: usize,
+    pub col: usize,
+    pub message: String,
+}
+
+impl LoaderWarning {
+    /// A warning at the position of the given schema value
+    fn new(value: &saphyr::MarkedYaml, message: String) -> LoaderWarning {
+        let line_col = LineCol::from(value);
+        LoaderWarning {
+            line: line_col.line,
+            col: line_col.col,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for LoaderWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}:{}] {}", self.line, self.col, self.message)
+    }
+}
+
 pub fn load_file<S: Into<String>>(path: S) -> Result<RootSchema> {
+    load_file_with_options(path, &LoaderOptions::default())
+}
+
+pub fn load_file_with_options<S: Into<String>>(
+    path: S,
+    options: &LoaderOptions,
+) -> Result<RootSchema> {
     let path_s = path.into();
     let fs_metadata = fs::metadata(&path_s)?;
     if !fs_metadata.is_file() {
         return Err(Error::FileNotFound(path_s.clone()));
     }
-    let s = fs::read_to_string(&path_s)?;
-    let docs = saphyr::Yaml::load_from_str(&s)?;
-    if docs.is_empty() {
+    // Relative references are resolved against the directory of each referring schema file
+    load_file_with_resolver(path_s, &FileSystemResolver::default(), options)
+}
+
+/// Load a schema file, using the given SchemaResolver to locate and read it, and every
+/// schema document it references
+pub fn load_file_with_resolver<S: Into<String>>(
+    path: S,
+    resolver: &dyn SchemaResolver,
+    options: &LoaderOptions,
+) -> Result<RootSchema> {
+    let location = resolver.resolve(None, &path.into())?;
+    let s = resolver.read(&location)?;
+    load_from_str_with_resolver(&s, Some(location), resolver, options)
+}
+
+pub fn load_from_str(s: &str) -> Result<RootSchema> {
+    load_from_str_with_options(s, &LoaderOptions::default())
+}
+
+pub fn load_from_str_with_options(s: &str, options: &LoaderOptions) -> Result<RootSchema> {
+    load_from_str_with_resolver(s, None, &FileSystemResolver::default(), options)
+}
+
+/// Load a schema from a string, using the given SchemaResolver to load the schema documents
+/// it references. `location` is where the schema came from, if anywhere.
+pub fn load_from_str_with_resolver(
+    s: &str,
+    location: Option<String>,
+    resolver: &dyn SchemaResolver,
+    options: &LoaderOptions,
+) -> Result<RootSchema> {
+    let docs = saphyr::MarkedYaml::load_from_str(s)?;
+    let Some(mut doc) = docs.into_iter().next() else {
         return Ok(RootSchema::new(YamlSchema::Empty)); // empty schema
+    };
+    let mut warnings = Vec::new();
+    remove_unknown_keywords(&mut doc, options, &mut warnings)?;
+    let mut reference_loader = ReferenceLoader::new(resolver);
+    if let Some(location) = &location {
+        reference_loader.register(location, location);
+    }
+    if let Some(id) = get_id(&doc) {
+        reference_loader.register(&id, location.as_deref().unwrap_or(&id));
+    }
+    reference_loader.collect_refs(&mut doc, location.as_deref())?;
+
+    // Load the external schema documents breadth-first, so that each document is only loaded
+    // once, even if there are cyclic references between documents
+    let mut external_docs = Vec::new();
+    while let Some(external_location) = reference_loader.pending.pop_front() {
+        let s = resolver.read(&external_location)?;
+        let docs = saphyr::MarkedYaml::load_from_str(&s)?;
+        let mut external_doc = docs.into_iter().next();
+        if let Some(external_doc) = &mut external_doc {
+            if let Some(id) = get_id(external_doc) {
+                reference_loader.register(&id, &external_location);
+            }
+            reference_loader.collect_refs(external_doc, Some(&external_location))?;
+        }
+        external_docs.push((external_location, external_doc));
+    }
+
+    // Now that every `$id` is known, rewrite the references and construct the schemas
+    reference_loader.rewrite_refs(&mut doc, location.as_deref(), false)?;
+    let mut root_schema = load_from_doc(&doc)?;
+    root_schema.location = location;
+    root_schema.warnings = warnings;
+    for (external_location, external_doc) in &mut external_docs {
+        let mut external_schema = match external_doc {
+            Some(doc) => {
+                let in_schema = |e: Error| e.in_schema(external_location);
+                let mut warnings = Vec::new();
+                remove_unknown_keywords(doc, options, &mut warnings).map_err(in_schema)?;
+                reference_loader.rewrite_refs(doc, Some(external_location), true)?;
+                let mut external_schema = load_from_doc(doc).map_err(in_schema)?;
+                external_schema.warnings = warnings;
+                external_schema
+            }
+            None => RootSchema::new(YamlSchema::Empty),
+        };
+        external_schema.location = Some(external_location.clone());
+        root_schema
+            .external_schemas
+            .insert(external_location.clone(), external_schema);
+    }
+    check_refs(&root_schema, &mut doc)?;
+    for (external_location, external_doc) in &mut external_docs {
+        if let Some(doc) = external_doc {
+            check_refs(&root_schema, doc).map_err(|e| e.in_schema(external_location))?;
+        }
+    }
+    Ok(root_schema)
+}
+
+/// The keywords that apply to any schema, whatever its type
+const KEYWORDS: &[&str] = &[
+    "$schema",
+    "$id",
+    "$ref",
+    "$defs",
+    "definitions",
+    "type",
+    "enum",
+    "const",
+    "allOf",
+    "anyOf",
+    "oneOf",
+    "not",
+    "if",
+    "then",
+    "else",
+];
+
+/// The annotation keywords, which describe a schema without affecting validation
+const ANNOTATION_KEYWORDS: &[&str] = &[
+    "title",
+    "description",
+    "default",
+    "examples",
+    "deprecated",
+    "readOnly",
+    "writeOnly",
+    "$comment",
+];
+
+/// Returns true if the loader knows the keyword, even if it doesn't apply to every type
+fn is_known_keyword(keyword: &str) -> bool {
+    [
+        KEYWORDS,
+        ANNOTATION_KEYWORDS,
+        ARRAY_KEYWORDS,
+        NUMBER_KEYWORDS,
+        OBJECT_KEYWORDS,
+        STRING_KEYWORDS,
+    ]
+    .iter()
+    .any(|keywords| keywords.contains(&keyword))
+}
+
+/// Returns true if the keyword only applies to some types, e.g. `minLength`
+fn is_type_keyword(keyword: &str) -> bool {
+    [
+        ARRAY_KEYWORDS,
+        NUMBER_KEYWORDS,
+        OBJECT_KEYWORDS,
+        STRING_KEYWORDS,
+    ]
+    .iter()
+    .any(|keywords| keywords.contains(&keyword))
+}
+
+/// Returns the type names of a `type:` value, or None if it isn't a string or an array of
+/// strings, which the constructor reports
+fn type_names(value: &saphyr::MarkedYaml) -> Option<Vec<String>> {
+    match &value.data {
+        saphyr::YamlData::String(type_name) => Some(vec![type_name.clone()]),
+        saphyr::YamlData::Array(values) => values
+            .iter()
+            .map(|value| value.data.as_str().map(String::from))
+            .collect(),
+        _ => None,
+    }
+}
+
+/// Remove the keywords that the loader doesn't know from a schema and its subschemas, so that
+/// the constructors only see known keywords. Depending on `options.unknown_keywords`, the first
+/// unknown keyword is an error, or each one is a warning, or they're removed silently. Unless
+/// it's strict, keywords that don't apply to the schema's `type` are removed the same way.
+fn remove_unknown_keywords(
+    schema: &mut saphyr::MarkedYaml,
+    options: &LoaderOptions,
+    warnings: &mut Vec<LoaderWarning>,
+) -> Result<()> {
+    let saphyr::YamlData::Hash(hash) = &mut schema.data else {
+        return Ok(());
+    };
+    let type_names = hash.get(&sys("type")).and_then(type_names);
+    let mut ignored_keys = Vec::new();
+    for (key, value) in hash.iter_mut() {
+        let Some(keyword) = key.data.as_str() else {
+            continue;
+        };
+        if options.allow_extensions && keyword.starts_with("x-") {
+            ignored_keys.push(key.clone());
+            continue;
+        }
+        if !is_known_keyword(keyword) {
+            match options.unknown_keywords {
+                UnknownKeywords::Strict => {
+                    return Err(schema_error!(key, "Unknown keyword '{}'", keyword))
+                }
+                UnknownKeywords::Warn => warnings.push(LoaderWarning::new(
+                    key,
+                    format!("Unknown keyword '{}' is ignored", keyword),
+                )),
+                UnknownKeywords::Ignore => (),
+            }
+            ignored_keys.push(key.clone());
+            continue;
+        }
+        // A keyword that doesn't apply to the type is ignored like an unknown keyword, except in
+        // strict mode, where the constructor reports it
+        if let Some(type_names) = &type_names {
+            let strict = options.unknown_keywords == UnknownKeywords::Strict;
+            let applies = type_names
+                .iter()
+                .any(|type_name| keywords_for_type(type_name).contains(&keyword));
+            if !strict && is_type_keyword(keyword) && !applies {
+                if options.unknown_keywords == UnknownKeywords::Warn {
+                    let type_value = match type_names.as_slice() {
+                        [type_name] => type_name.clone(),
+                        type_names => format!("[{}]", type_names.join(", ")),
+                    };
+                    warnings.push(LoaderWarning::new(
+                        key,
+                        format!(
+                            "Keyword '{}' doesn't apply to a `type: {}` schema and is ignored",
+                            keyword, type_value
+                        ),
+                    ));
+                }
+                ignored_keys.push(key.clone());
+                continue;
+            }
+        }
+        for_each_subschema(keyword, value, &mut |subschema| {
+            remove_unknown_keywords(subschema, options, warnings)
+        })?;
+    }
+    for key in ignored_keys {
+        hash.remove(&key);
     }
-    load_from_doc(docs.first().unwrap())
+    Ok(())
 }
 
-pub fn load_from_doc(doc: &saphyr::Yaml) -> Result<RootSchema> {
+/// Calls `f` with each subschema in the value of a keyword, e.g. each schema in `properties`.
+/// The values of other keywords, such as `default` or `enum`, are data rather than schemas.
+fn for_each_subschema<F: FnMut(&mut saphyr::MarkedYaml) -> Result<()>>(
+    keyword: &str,
+    value: &mut saphyr::MarkedYaml,
+    f: &mut F,
+) -> Result<()> {
+    match (keyword, &mut value.data) {
+        (
+            "properties" | "patternProperties" | "$defs" | "definitions" | "dependentSchemas"
+            | "dependencies",
+            saphyr::YamlData::Hash(subschemas),
+        ) => {
+            for subschema in subschemas.values_mut() {
+                f(subschema)?;
+            }
+        }
+        ("allOf" | "anyOf" | "oneOf" | "prefixItems", saphyr::YamlData::Array(subschemas)) => {
+            for subschema in subschemas.iter_mut() {
+                f(subschema)?;
+            }
+        }
+        ("items" | "additionalProperties" | "contains" | "not" | "if" | "then" | "else", _) => {
+            f(value)?;
+        }
+        _ => (),
+    }
+    Ok(())
+}
+
+/// Returns the `$id` of a schema document, if it has one
+fn get_id(doc: &saphyr::MarkedYaml) -> Option<String> {
+    doc.data
+        .as_hash()
+        .and_then(|hash| hash.get(&sys("$id")))
+        .and_then(|id| id.data.as_str())
+        .map(String::from)
+}
+
+/// Calls `f` with every `$ref` value in a schema and its subschemas that is a string
+fn for_each_ref<F: FnMut(&mut saphyr::MarkedYaml) -> Result<()>>(
+    schema: &mut saphyr::MarkedYaml,
+    f: &mut F,
+) -> Result<()> {
+    let saphyr::YamlData::Hash(hash) = &mut schema.data else {
+        return Ok(());
+    };
+    for (key, value) in hash.iter_mut() {
+        match (key.data.as_str(), &value.data) {
+            (Some("$ref"), saphyr::YamlData::String(_)) => f(value)?,
+            (Some(keyword), _) => {
+                for_each_subschema(keyword, value, &mut |subschema| for_each_ref(subschema, f))?
+            }
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Finds the external schema documents referenced by `$ref`s, and rewrites each `$ref`
+/// so it can be resolved against the root schema at validation time
+struct ReferenceLoader<'a> {
+    resolver: &'a dyn SchemaResolver,
+    /// The locations of all the documents seen so far, keyed by location and by `$id`
+    locations: HashMap<String, String>,
+    /// The locations of referenced documents, keyed by the referring document and the URI
+    resolved: HashMap<(Option<String>, String), String>,
+    /// The locations of the documents that still need to be loaded
+    pending: VecDeque<String>,
+}
+
+impl<'a> ReferenceLoader<'a> {
+    fn new(resolver: &'a dyn SchemaResolver) -> Self {
+        ReferenceLoader {
+            resolver,
+            locations: HashMap::new(),
+            resolved: HashMap::new(),
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn register(&mut self, key: &str, location: &str) {
+        self.locations.insert(key.to_string(), location.to_string());
+    }
+
+    /// Resolve the URIs of all the `$ref`s in a document, and queue the documents that haven't
+    /// been seen before. URIs that can't be resolved yet may still turn out to be the `$id`
+    /// of a document that is loaded later, so they're only reported by `rewrite_refs`.
+    fn collect_refs(&mut self, doc: &mut saphyr::MarkedYaml, base: Option<&str>) -> Result<()> {
+        for_each_ref(doc, &mut |value| {
+            let reference = value.data.as_str().unwrap_or_default();
+            let (uri, _) = reference.split_once('#').unwrap_or((reference, ""));
+            let key = (base.map(String::from), uri.to_string());
+            if uri.is_empty()
+                || self.locations.contains_key(uri)
+                || self.resolved.contains_key(&key)
+            {
+                return Ok(());
+            }
+            if let Ok(location) = self.resolver.resolve(base, uri) {
+                if !self.locations.contains_key(&location) {
+                    self.register(&location, &location);
+                    self.pending.push_back(location.clone());
+                }
+                self.resolved.insert(key, location);
+            }
+            Ok(())
+        })
+    }
+
+    /// Rewrite every `$ref` in the document at `base` to `<location>#<fragment>`.
+    /// In-document references in the root document are left as they are, since they
+    /// resolve against the root schema anyway.
+    fn rewrite_refs(
+        &self,
+        doc: &mut saphyr::MarkedYaml,
+        base: Option<&str>,
+        is_external: bool,
+    ) -> Result<()> {
+        for_each_ref(doc, &mut |value| {
+            let reference = value.data.as_str().unwrap_or_default();
+            let (uri, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+            let rewritten = if !uri.is_empty() {
+                let key = (base.map(String::from), uri.to_string());
+                let location = match self.locations.get(uri).or(self.resolved.get(&key)) {
+                    Some(location) => location.clone(),
+                    // Let the resolver report why the URI can't be resolved
+                    None => self.resolver.resolve(base, uri)?,
+                };
+                format!("{}#{}", location, fragment)
+            } else if is_external {
+                format!("{}#{}", base.unwrap_or_default(), fragment)
+            } else {
+                return Ok(());
+            };
+            value.data = saphyr::YamlData::String(rewritten);
+            Ok(())
+        })
+    }
+}
+
+/// Report the first `$ref` in the document that doesn't resolve against the root schema, once
+/// every document has been loaded, so that it's reported where it is rather than only when a
+/// value is validated against it
+fn check_refs(root_schema: &RootSchema, doc: &mut saphyr::MarkedYaml) -> Result<()> {
+    for_each_ref(doc, &mut |value| match value.data.as_str() {
+        Some(reference) if root_schema.resolve(reference).is_none() => Err(schema_error!(
+            value,
+            "Cannot resolve reference: {}",
+            reference
+        )),
+        _ => Ok(()),
+    })
+}
+
+pub fn load_from_doc(doc: &saphyr::MarkedYaml) -> Result<RootSchema> {
     let mut loader = RootLoader::new();
-    match doc {
-        saphyr::Yaml::Boolean(r#bool) => {
+    match &doc.data {
+        saphyr::YamlData::Boolean(r#bool) => {
             loader.set_schema(YamlSchema::BooleanLiteral(*r#bool));
         }
-        saphyr::Yaml::Hash(hash) => {
+        saphyr::YamlData::Hash(hash) => {
             loader.load_root_schema(hash)?;
         }
-        saphyr::Yaml::Null => {
+        saphyr::YamlData::Null => {
             loader.set_schema(YamlSchema::TypeNull);
         }
-        saphyr::Yaml::String(s) => match s.as_str() {
+        saphyr::YamlData::String(s) => match s.as_str() {
             "true" => {
                 loader.set_schema(YamlSchema::BooleanLiteral(true));
             }
             "false" => {
                 loader.set_schema(YamlSchema::BooleanLiteral(false));
             }
-            s => return Err(generic_error!("Expected true or false, but got: {}", s)),
+            s => return Err(schema_error!(doc, "Expected true or false, but got: {}", s)),
         },
-        _ => {
-            unimplemented!()
+        v => {
+            return Err(schema_error!(
+                doc,
+                "Expected a boolean or a hash, but got: {:?}",
+                v
+            ))
         }
     }
     Ok(loader.into()) // See From<Loader> for RootSchema below
@@ -67,6 +532,7 @@ pub fn load_from_doc(doc: &saphyr::Yaml) -> Result<RootSchema> {
 struct RootLoader {
     pub id: Option<String>,
     pub meta_schema: Option<String>,
+    pub defs: Option<HashMap<String, YamlSchema>>,
     pub schema: Option<YamlSchema>,
 }
 
@@ -82,130 +548,381 @@ impl RootLoader {
         self.schema = Some(schema);
     }
 
-    fn load_root_schema(&mut self, hash: &saphyr::Hash) -> Result<()> {
-        let id_key = saphyr::Yaml::String(String::from("$id"));
-        let schema_key = saphyr::Yaml::String(String::from("$schema"));
-        if let Some(id) = hash.get(&id_key) {
+    fn load_root_schema(&mut self, hash: &MarkedHash) -> Result<()> {
+        if let Some(id) = hash.get(&sys("$id")) {
             self.id = Some(yaml_to_string(id, "$id value must be a string")?);
         }
-        if let Some(schema) = hash.get(&schema_key) {
+        if let Some(schema) = hash.get(&sys("$schema")) {
             self.meta_schema = Some(yaml_to_string(schema, "$schema value must be a string")?);
         }
-        self.schema = Some(YamlSchema::construct(hash)?);
+        // `definitions` is the legacy (draft-07 and earlier) spelling of `$defs`
+        for defs_key in ["definitions", "$defs"] {
+            if let Some(value) = hash.get(&sys(defs_key)) {
+                let defs = load_properties(load_hash(value, defs_key)?)?;
+                self.defs.get_or_insert_with(HashMap::new).extend(defs);
+            }
+        }
+        // The root keywords have been handled, so construct the schema from the remaining keys
+        let mut schema_hash = hash.clone();
+        for key in ["$id", "$schema", "$defs", "definitions"] {
+            schema_hash.remove(&sys(key));
+        }
+        if schema_hash.is_empty() {
+            self.schema = Some(YamlSchema::Empty);
+        } else {
+            self.schema = Some(YamlSchema::construct(&schema_hash)?);
+        }
         Ok(())
     }
 }
 
 impl Constructor<YamlSchema> for YamlSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<YamlSchema> {
-        if hash.contains_key(&sys("type")) {
-            match TypedSchema::construct(hash) {
-                Ok(typed_schema) => Ok(typed_schema.into()),
-                Err(e) => Err(e),
-            }
-        } else if hash.contains_key(&sys("enum")) {
-            let enum_schema = EnumSchema::construct(hash)?;
-            return Ok(YamlSchema::Enum(enum_schema));
-        } else if hash.contains_key(&sys("const")) {
-            let const_schema = ConstSchema::construct(hash)?;
-            return Ok(YamlSchema::Const(const_schema));
-        } else if hash.contains_key(&sys("anyOf")) {
-            let any_of_schema = AnyOfSchema::construct(hash)?;
-            return Ok(YamlSchema::AnyOf(any_of_schema));
-        } else if hash.contains_key(&sys("oneOf")) {
-            let one_of_schema = OneOfSchema::construct(hash)?;
-            return Ok(YamlSchema::OneOf(one_of_schema));
-        } else if hash.contains_key(&sys("not")) {
-            let not_schema = NotSchema::construct(hash)?;
-            return Ok(YamlSchema::Not(not_schema));
+    fn construct(hash: &MarkedHash) -> Result<YamlSchema> {
+        let (annotations, hash) = load_annotations(hash)?;
+        Ok(with_annotations(annotations, construct_schema(&hash)?))
+    }
+}
+
+/// Wrap a schema in an AnnotatedSchema, unless it doesn't have any annotations
+fn with_annotations(annotations: Annotations, schema: YamlSchema) -> YamlSchema {
+    if annotations.is_empty() {
+        schema
+    } else {
+        YamlSchema::Annotated(AnnotatedSchema {
+            annotations,
+            schema: Box::new(schema),
+        })
+    }
+}
+
+/// Construct a schema from a hash without any annotation keywords
+fn construct_schema(hash: &MarkedHash) -> Result<YamlSchema> {
+    if hash.contains_key(&sys("if")) {
+        let if_then_else_schema = YamlSchema::IfThenElse(IfThenElseSchema::construct(hash)?);
+        // Any other keywords alongside `if` apply unconditionally, as if by `allOf`
+        let mut other_keywords = hash.clone();
+        for key in ["if", "then", "else"] {
+            other_keywords.remove(&sys(key));
+        }
+        if other_keywords.is_empty() {
+            Ok(if_then_else_schema)
         } else {
-            unimplemented!()
+            let schema = construct_without_type(&other_keywords)?;
+            Ok(YamlSchema::AllOf(AllOfSchema {
+                all_of: vec![schema, if_then_else_schema],
+                implicit: true,
+            }))
+        }
+    } else if let Some(reference) = hash.get(&sys("$ref")) {
+        let ref_schema = YamlSchema::Ref(RefSchema::new(load_string_value(reference)?));
+        // In 2020-12, `$ref` is an applicator, so any other keywords alongside it apply too
+        let mut other_keywords = hash.clone();
+        other_keywords.remove(&sys("$ref"));
+        if other_keywords.is_empty() {
+            Ok(ref_schema)
+        } else {
+            let schema = construct_without_type(&other_keywords)?;
+            Ok(YamlSchema::AllOf(AllOfSchema {
+                all_of: vec![ref_schema, schema],
+                implicit: true,
+            }))
+        }
+    } else if hash.contains_key(&sys("type")) {
+        match TypedSchema::construct(hash) {
+            Ok(typed_schema) => Ok(typed_schema.into()),
+            Err(e) => Err(e),
+        }
+    } else if hash.contains_key(&sys("enum")) {
+        let enum_schema = EnumSchema::construct(hash)?;
+        Ok(YamlSchema::Enum(enum_schema))
+    } else if hash.contains_key(&sys("const")) {
+        let const_schema = ConstSchema::construct(hash)?;
+        Ok(YamlSchema::Const(const_schema))
+    } else if hash.contains_key(&sys("allOf")) {
+        let all_of_schema = AllOfSchema::construct(hash)?;
+        Ok(YamlSchema::AllOf(all_of_schema))
+    } else if hash.contains_key(&sys("anyOf")) {
+        let any_of_schema = AnyOfSchema::construct(hash)?;
+        Ok(YamlSchema::AnyOf(any_of_schema))
+    } else if hash.contains_key(&sys("oneOf")) {
+        let one_of_schema = OneOfSchema::construct(hash)?;
+        Ok(YamlSchema::OneOf(one_of_schema))
+    } else if hash.contains_key(&sys("not")) {
+        let not_schema = NotSchema::construct(hash)?;
+        Ok(YamlSchema::Not(not_schema))
+    } else {
+        match hash.keys().next() {
+            // None of the keywords can be used on their own, e.g. `properties` without a `type`
+            Some(key) => Err(schema_error!(
+                key,
+                "Unsupported keyword {} without a type",
+                keyword_name(key)
+            )),
+            None => Ok(YamlSchema::Empty),
         }
     }
 }
 
+/// Construct a schema that may not have a `type`, e.g. the keywords alongside a `$ref`. As in
+/// 2020-12, its type-specific keywords (`minLength`, `properties`, ...) then only apply to
+/// values of the type they're for, and values of any other type are accepted.
+fn construct_without_type(hash: &MarkedHash) -> Result<YamlSchema> {
+    let (type_keywords, other_keywords): (MarkedHash, MarkedHash) = hash
+        .iter()
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .partition(|(key, _)| key.data.as_str().is_some_and(is_type_keyword));
+    if hash.contains_key(&sys("type")) || type_keywords.is_empty() {
+        return construct_schema(hash);
+    }
+    let types = ["null", "boolean", "number", "string", "array", "object"]
+        .iter()
+        .map(|type_name| {
+            let mut type_hash = hash_for_type(&type_keywords, type_name);
+            type_hash.insert(sys("type"), sys(type_name));
+            TypedSchema::construct(&type_hash)
+        })
+        .collect::<Result<Vec<TypedSchema>>>()?;
+    let typed_schema = TypedSchema::MultipleTypes(MultipleTypesSchema { types }).into();
+    if other_keywords.is_empty() {
+        Ok(typed_schema)
+    } else {
+        Ok(YamlSchema::AllOf(AllOfSchema {
+            all_of: vec![construct_schema(&other_keywords)?, typed_schema],
+            implicit: true,
+        }))
+    }
+}
+
 impl Constructor<TypedSchema> for TypedSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<TypedSchema> {
-        let type_key = saphyr::Yaml::String(String::from("type"));
-        if hash.contains_key(&type_key) {
-            let value = hash.get(&type_key).unwrap();
-            match value {
-                saphyr::Yaml::String(s) => match s.as_str() {
-                    "array" => {
-                        let array_schema = ArraySchema::construct(hash)?;
-                        Ok(TypedSchema::Array(array_schema))
-                    }
-                    "boolean" => Ok(TypedSchema::BooleanSchema),
-                    "integer" => {
-                        let integer_schema = IntegerSchema::construct(hash)?;
-                        Ok(TypedSchema::Integer(integer_schema))
-                    }
-                    "number" => {
-                        let number_schema = NumberSchema::construct(hash)?;
-                        Ok(TypedSchema::Number(number_schema))
-                    }
-                    "object" => {
-                        let object_schema = ObjectSchema::construct(hash)?;
-                        Ok(TypedSchema::Object(object_schema))
-                    }
-                    "string" => {
-                        let string_schema = StringSchema::construct(hash)?;
-                        Ok(TypedSchema::String(string_schema))
-                    }
-                    s => Err(unsupported_type!(s.to_string())),
-                },
-                saphyr::Yaml::Null => Ok(TypedSchema::Null),
-                v => Err(unsupported_type!(
-                    "Expected type: string, but got: {:#?}",
-                    v
+    fn construct(hash: &MarkedHash) -> Result<TypedSchema> {
+        let Some(value) = hash.get(&sys("type")) else {
+            return Err(generic_error!("No type key found in hash"));
+        };
+        match &value.data {
+            saphyr::YamlData::Array(_) => match load_type_value(value)? {
+                TypeValue::Array(type_names) => {
+                    let types = type_names
+                        .iter()
+                        .map(|type_name| TypedSchema::construct(&hash_for_type(hash, type_name)))
+                        .collect::<Result<Vec<TypedSchema>>>()?;
+                    Ok(TypedSchema::MultipleTypes(MultipleTypesSchema { types }))
+                }
+                TypeValue::Single(v) => Err(schema_error!(
+                    &v,
+                    "Expected an array of types, but got: {:?}",
+                    v.data
                 )),
+            },
+            saphyr::YamlData::String(s) => match s.as_str() {
+                "array" => {
+                    let array_schema = ArraySchema::construct(hash)?;
+                    Ok(TypedSchema::Array(array_schema))
+                }
+                "boolean" => Ok(TypedSchema::BooleanSchema),
+                "integer" => {
+                    let integer_schema = IntegerSchema::construct(hash)?;
+                    Ok(TypedSchema::Integer(integer_schema))
+                }
+                "number" => {
+                    let number_schema = NumberSchema::construct(hash)?;
+                    Ok(TypedSchema::Number(number_schema))
+                }
+                "object" => {
+                    let object_schema = ObjectSchema::construct(hash)?;
+                    Ok(TypedSchema::Object(object_schema))
+                }
+                "string" => {
+                    let string_schema = StringSchema::construct(hash)?;
+                    Ok(TypedSchema::String(string_schema))
+                }
+                "null" => Ok(TypedSchema::Null),
+                s => Err(schema_error!(value, "Unsupported type '{}'!", s)),
+            },
+            saphyr::YamlData::Null => Ok(TypedSchema::Null),
+            v => Err(schema_error!(
+                value,
+                "Expected type: string, but got: {:?}",
+                v
+            )),
+        }
+    }
+}
+
+/// Load the value of `type:`, which is either a single type or an array of types
+fn load_type_value(value: &saphyr::MarkedYaml) -> Result<TypeValue> {
+    match &value.data {
+        saphyr::YamlData::Array(values) => {
+            let type_names = values
+                .iter()
+                .map(|v| match &v.data {
+                    saphyr::YamlData::String(s) => Ok(s.clone()),
+                    saphyr::YamlData::Null => Ok("null".to_string()),
+                    data => Err(schema_error!(
+                        v,
+                        "Expected type: string, but got: {:?}",
+                        data
+                    )),
+                })
+                .collect::<Result<Vec<String>>>()?;
+            Ok(TypeValue::Array(type_names))
+        }
+        _ => Ok(TypeValue::Single(value.clone())),
+    }
+}
+
+const ARRAY_KEYWORDS: &[&str] = &[
+    "items",
+    "prefixItems",
+    "contains",
+    "minItems",
+    "maxItems",
+    "uniqueItems",
+    "minContains",
+    "maxContains",
+];
+const NUMBER_KEYWORDS: &[&str] = &[
+    "minimum",
+    "maximum",
+    "exclusiveMinimum",
+    "exclusiveMaximum",
+    "multipleOf",
+];
+const OBJECT_KEYWORDS: &[&str] = &[
+    "properties",
+    "additionalProperties",
+    "minProperties",
+    "maxProperties",
+    "patternProperties",
+    "propertyNames",
+    "required",
+    "dependentRequired",
+    "dependentSchemas",
+    "dependencies",
+];
+const STRING_KEYWORDS: &[&str] = &["minLength", "maxLength", "pattern", "format"];
+
+/// Returns the keywords that only apply to the given type
+fn keywords_for_type(type_name: &str) -> &'static [&'static str] {
+    match type_name {
+        "array" => ARRAY_KEYWORDS,
+        "integer" | "number" => NUMBER_KEYWORDS,
+        "object" => OBJECT_KEYWORDS,
+        "string" => STRING_KEYWORDS,
+        _ => &[],
+    }
+}
+
+/// For a schema with multiple types, returns a copy of the schema hash with a single `type:`,
+/// and without the keywords that only apply to the other types
+fn hash_for_type(hash: &MarkedHash, type_name: &str) -> MarkedHash {
+    let all_type_keywords = [
+        ARRAY_KEYWORDS,
+        NUMBER_KEYWORDS,
+        OBJECT_KEYWORDS,
+        STRING_KEYWORDS,
+    ]
+    .concat();
+    let mut type_hash = MarkedHash::new();
+    for (key, value) in hash.iter() {
+        match key.data.as_str() {
+            Some("type") => {
+                // Keep the position of the `type:` value, for any errors in the single type
+                let type_value = saphyr::MarkedYaml {
+                    span: value.span,
+                    data: saphyr::YamlData::String(type_name.to_string()),
+                };
+                type_hash.insert(key.clone(), type_value);
+            }
+            Some(keyword)
+                if all_type_keywords.contains(&keyword)
+                    && !keywords_for_type(type_name).contains(&keyword) => {}
+            _ => {
+                type_hash.insert(key.clone(), value.clone());
             }
-        } else {
-            Err(generic_error!("No type key found in hash: {:#?}", hash))
         }
     }
+    type_hash
 }
 
-/// A Constructor constructs an object (a schema) from a saphyr::Hash
+/// A Constructor constructs an object (a schema) from a hash in a schema document
 pub trait Constructor<T> {
-    fn construct(hash: &saphyr::Hash) -> Result<T>;
+    fn construct(hash: &MarkedHash) -> Result<T>;
 }
 
-fn load_string_value(value: &saphyr::Yaml) -> Result<String> {
-    match value {
-        saphyr::Yaml::String(s) => Ok(s.clone()),
-        _ => Err(unsupported_type!(
+/// The name of a keyword for error messages, which is usually but not always a string
+fn keyword_name(key: &saphyr::MarkedYaml) -> String {
+    match &key.data {
+        saphyr::YamlData::String(s) => format!("'{}'", s),
+        data => format!("{:?}", data),
+    }
+}
+
+/// The error for a keyword that can't be used in the schema it appears in, e.g. `minLength`
+/// alongside `type: integer`
+fn unsupported_keyword(key: &saphyr::MarkedYaml, schema: &str) -> Error {
+    schema_error!(
+        key,
+        "Unsupported keyword {} in {} schema",
+        keyword_name(key),
+        schema
+    )
+}
+
+fn load_string_value(value: &saphyr::MarkedYaml) -> Result<String> {
+    match &value.data {
+        saphyr::YamlData::String(s) => Ok(s.clone()),
+        data => Err(schema_error!(
+            value,
             "Expected a string value, but got: {:?}",
-            value
+            data
         )),
     }
 }
 
 impl Constructor<ArraySchema> for ArraySchema {
-    fn construct(hash: &saphyr::Hash) -> Result<ArraySchema> {
+    fn construct(hash: &MarkedHash) -> Result<ArraySchema> {
         let mut array_schema = ArraySchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "contains" => {
-                        let yaml_schema = YamlSchema::construct(value.as_hash().unwrap())?;
+                        let yaml_schema = load_schema(value)?;
                         array_schema.contains = Some(Box::new(yaml_schema));
                     }
                     "items" => {
-                        let array_items = load_array_items(value)?;
+                        let array_items = load_bool_or_typed_schema(value, "items")?;
                         array_schema.items = Some(array_items);
                     }
                     "type" => {
                         let s = load_string_value(value)?;
                         if s != "array" {
-                            return Err(unsupported_type!("Expected type: array, but got: {}", s));
+                            return Err(schema_error!(
+                                value,
+                                "Expected type: array, but got: {}",
+                                s
+                            ));
                         }
                     }
                     "prefixItems" => {
-                        let prefix_items = load_array_of_schemas(value)?;
+                        let prefix_items = load_array_of_schemas(value, "prefixItems")?;
                         array_schema.prefix_items = Some(prefix_items);
                     }
-                    _ => unimplemented!(),
+                    "minItems" => {
+                        array_schema.min_items = Some(load_count(value)?);
+                    }
+                    "maxItems" => {
+                        array_schema.max_items = Some(load_count(value)?);
+                    }
+                    "uniqueItems" => {
+                        array_schema.unique_items = Some(load_boolean(value)?);
+                    }
+                    "minContains" => {
+                        array_schema.min_contains = Some(load_count(value)?);
+                    }
+                    "maxContains" => {
+                        array_schema.max_contains = Some(load_count(value)?);
+                    }
+                    _ => return Err(unsupported_keyword(key, "a `type: array`")),
                 }
             }
         }
@@ -214,33 +931,22 @@ impl Constructor<ArraySchema> for ArraySchema {
 }
 
 impl Constructor<ConstSchema> for ConstSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<ConstSchema> {
-        let const_key = sys("const");
-        let value = hash.get(&const_key).unwrap();
-        match value {
-            saphyr::Yaml::String(s) => Ok(ConstSchema {
-                r#const: ConstValue::string(s),
-            }),
-            saphyr::Yaml::Integer(i) => Ok(ConstSchema {
-                r#const: ConstValue::integer(*i),
-            }),
-            saphyr::Yaml::Real(s) => {
-                let f = s.parse::<f64>()?;
-                Ok(ConstSchema {
-                    r#const: ConstValue::float(f),
-                })
-            }
-            _ => unimplemented!(),
-        }
+    fn construct(hash: &MarkedHash) -> Result<ConstSchema> {
+        let Some(value) = hash.get(&sys("const")) else {
+            return Err(generic_error!("No const key found in hash"));
+        };
+        Ok(ConstSchema {
+            r#const: ConstValue::from_saphyr_yaml(value)?,
+        })
     }
 }
 
 impl Constructor<IntegerSchema> for IntegerSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<IntegerSchema> {
+    fn construct(hash: &MarkedHash) -> Result<IntegerSchema> {
         let mut integer_schema = IntegerSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "minimum" => {
                         integer_schema.minimum = Some(load_number(value)?);
                     }
@@ -254,18 +960,19 @@ impl Constructor<IntegerSchema> for IntegerSchema {
                         integer_schema.exclusive_maximum = Some(load_number(value)?);
                     }
                     "multipleOf" => {
-                        integer_schema.multiple_of = Some(load_number(value)?);
+                        integer_schema.multiple_of = Some(load_multiple_of(value)?);
                     }
                     "type" => {
                         let s = load_string_value(value)?;
                         if s != "integer" {
-                            return Err(unsupported_type!(
+                            return Err(schema_error!(
+                                value,
                                 "Expected type: integer, but got: {}",
                                 s
                             ));
                         }
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `type: integer`")),
                 }
             }
         }
@@ -274,86 +981,119 @@ impl Constructor<IntegerSchema> for IntegerSchema {
 }
 
 impl Constructor<EnumSchema> for EnumSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<EnumSchema> {
-        let enum_key = sys("enum");
-        let value = hash.get(&enum_key).unwrap();
-        match value {
-            saphyr::Yaml::Array(values) => {
+    fn construct(hash: &MarkedHash) -> Result<EnumSchema> {
+        let Some(value) = hash.get(&sys("enum")) else {
+            return Err(generic_error!("No enum key found in hash"));
+        };
+        match &value.data {
+            saphyr::YamlData::Array(values) => {
                 let enum_values = load_enum_values(values)?;
                 Ok(EnumSchema {
                     r#enum: enum_values,
                 })
             }
-            v => Err(generic_error!("enum: Expected an array, but got: {:#?}", v)),
+            v => Err(schema_error!(
+                value,
+                "enum: Expected an array, but got: {:?}",
+                v
+            )),
         }
     }
 }
 
 impl Constructor<ObjectSchema> for ObjectSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<ObjectSchema> {
+    fn construct(hash: &MarkedHash) -> Result<ObjectSchema> {
         let mut object_schema = ObjectSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "properties" => {
-                        let properties = load_properties(value.as_hash().unwrap())?;
+                        let properties = load_properties(load_hash(value, "properties")?)?;
                         object_schema.properties = Some(properties);
                     }
                     "additionalProperties" => {
-                        let additional_properties = load_additional_properties(value)?;
+                        let additional_properties =
+                            load_bool_or_typed_schema(value, "additionalProperties")?;
                         object_schema.additional_properties = Some(additional_properties);
                     }
                     "minProperties" => {
-                        object_schema.min_properties = Some(load_integer(value)? as usize);
+                        object_schema.min_properties = Some(load_count(value)?);
                     }
                     "maxProperties" => {
-                        object_schema.max_properties = Some(load_integer(value)? as usize);
+                        object_schema.max_properties = Some(load_count(value)?);
                     }
                     "patternProperties" => {
-                        let pattern_properties = load_properties(value.as_hash().unwrap())?;
+                        let pattern_properties =
+                            load_properties(load_hash(value, "patternProperties")?)?;
                         object_schema.pattern_properties = Some(pattern_properties);
                     }
-                    "propertyNames" => {
-                        if !value.is_hash() {
-                            return Err(unsupported_type!(
-                                "propertyNames: Expected a hash, but got: {:?}",
-                                value
-                            ));
+                    "dependentRequired" => {
+                        let hash = load_hash(value, "dependentRequired")?;
+                        for (property, required) in hash.iter() {
+                            let property = load_string_value(property)?;
+                            object_schema
+                                .dependent_required
+                                .get_or_insert_with(HashMap::new)
+                                .insert(property, load_array_of_strings(required)?);
                         }
-                        let hash = value.as_hash().unwrap();
-                        if !hash.contains_key(&sys("pattern")) {
-                            return Err(generic_error!(
+                    }
+                    "dependentSchemas" => {
+                        // A dependent schema usually only has object keywords, e.g. `required`
+                        let hash = load_hash(value, "dependentSchemas")?;
+                        for (property, schema) in hash.iter() {
+                            let property = load_string_value(property)?;
+                            object_schema
+                                .dependent_schemas
+                                .get_or_insert_with(HashMap::new)
+                                .insert(property, load_schema_without_type(schema)?);
+                        }
+                    }
+                    // `dependencies` is the draft-07 spelling of both `dependentRequired` (when the
+                    // value is an array) and `dependentSchemas` (when the value is a schema)
+                    "dependencies" => {
+                        let hash = load_hash(value, "dependencies")?;
+                        for (property, dependency) in hash.iter() {
+                            let property = load_string_value(property)?;
+                            match &dependency.data {
+                                saphyr::YamlData::Array(_) => {
+                                    object_schema
+                                        .dependent_required
+                                        .get_or_insert_with(HashMap::new)
+                                        .insert(property, load_array_of_strings(dependency)?);
+                                }
+                                _ => {
+                                    object_schema
+                                        .dependent_schemas
+                                        .get_or_insert_with(HashMap::new)
+                                        .insert(property, load_schema_without_type(dependency)?);
+                                }
+                            }
+                        }
+                    }
+                    "propertyNames" => {
+                        let hash = load_hash(value, "propertyNames")?;
+                        let Some(pattern) = hash.get(&sys("pattern")) else {
+                            return Err(schema_error!(
+                                value,
                                 "propertyNames: Missing required key: pattern"
                             ));
-                        }
-                        let pattern = load_string_value(hash.get(&sys("pattern")).unwrap())?;
-                        object_schema.property_names = Some(pattern);
+                        };
+                        object_schema.property_names = Some(load_string_value(pattern)?);
                     }
                     "required" => {
-                        if !value.is_array() {
-                            return Err(unsupported_type!(
-                                "required: Expected an array, but got: {:?}",
-                                value
-                            ));
-                        }
-                        let array = value.as_vec().unwrap();
-                        object_schema.required = Some(
-                            array
-                                .iter()
-                                .map(|v| match v {
-                                    saphyr::Yaml::String(s) => Ok(s.clone()),
-                                    _ => unimplemented!(),
-                                })
-                                .collect::<Result<Vec<String>>>()?,
-                        );
+                        object_schema.required = Some(load_array_of_strings(value)?);
                     }
                     "type" => {
                         let s = load_string_value(value)?;
                         if s != "object" {
-                            return Err(unsupported_type!("Expected type: object, but got: {}", s));
+                            return Err(schema_error!(
+                                value,
+                                "Expected type: object, but got: {}",
+                                s
+                            ));
                         }
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `type: object`")),
                 }
             }
         }
@@ -361,29 +1101,124 @@ impl Constructor<ObjectSchema> for ObjectSchema {
     }
 }
 
-fn load_array_of_schemas(value: &saphyr::Yaml) -> Result<Vec<YamlSchema>> {
-    match value {
-        saphyr::Yaml::Array(values) => values
+fn load_hash<'a>(value: &'a saphyr::MarkedYaml, keyword: &str) -> Result<&'a MarkedHash> {
+    match &value.data {
+        saphyr::YamlData::Hash(hash) => Ok(hash),
+        v => Err(schema_error!(
+            value,
+            "{}: Expected a hash, but got: {:?}",
+            keyword,
+            v
+        )),
+    }
+}
+
+fn load_array_of_strings(value: &saphyr::MarkedYaml) -> Result<Vec<String>> {
+    match &value.data {
+        saphyr::YamlData::Array(values) => values.iter().map(load_string_value).collect(),
+        v => Err(schema_error!(
+            value,
+            "Expected an array of strings, but got: {:?}",
+            v
+        )),
+    }
+}
+
+fn load_array_of_schemas(value: &saphyr::MarkedYaml, keyword: &str) -> Result<Vec<YamlSchema>> {
+    match &value.data {
+        saphyr::YamlData::Array(values) => values
             .iter()
-            .map(|v| match v {
-                saphyr::Yaml::Hash(hash) => YamlSchema::construct(hash),
-                _ => unimplemented!(),
-            })
+            .map(load_schema)
             .collect::<Result<Vec<YamlSchema>>>(),
-        _ => unimplemented!(),
+        v => Err(schema_error!(
+            value,
+            "{}: Expected an array of schemas, but got: {:?}",
+            keyword,
+            v
+        )),
+    }
+}
+
+impl Constructor<AllOfSchema> for AllOfSchema {
+    fn construct(hash: &MarkedHash) -> Result<AllOfSchema> {
+        let mut all_of_schema = AllOfSchema::default();
+        for (key, value) in hash.iter() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
+                    "allOf" => {
+                        all_of_schema.all_of = load_array_of_schemas(value, "allOf")?;
+                    }
+                    _ => return Err(unsupported_keyword(key, "an `allOf`")),
+                }
+            }
+        }
+        Ok(all_of_schema)
+    }
+}
+
+impl Constructor<IfThenElseSchema> for IfThenElseSchema {
+    /// Constructs an IfThenElseSchema from the `if`, `then` and `else` keys, ignoring all others
+    fn construct(hash: &MarkedHash) -> Result<IfThenElseSchema> {
+        let mut if_then_else_schema = IfThenElseSchema::default();
+        for (key, value) in hash.iter() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
+                    "if" => {
+                        if_then_else_schema.r#if = Box::new(load_schema_without_type(value)?);
+                    }
+                    "then" => {
+                        if_then_else_schema.then = Some(Box::new(load_schema_without_type(value)?));
+                    }
+                    "else" => {
+                        if_then_else_schema.r#else =
+                            Some(Box::new(load_schema_without_type(value)?));
+                    }
+                    _ => (),
+                }
+            }
+        }
+        Ok(if_then_else_schema)
+    }
+}
+
+/// Load a subschema, which is either a hash or a boolean literal
+fn load_schema(value: &saphyr::MarkedYaml) -> Result<YamlSchema> {
+    match &value.data {
+        saphyr::YamlData::Boolean(b) => Ok(YamlSchema::BooleanLiteral(*b)),
+        saphyr::YamlData::Hash(hash) => YamlSchema::construct(hash),
+        data => Err(schema_error!(
+            value,
+            "Expected a boolean or a hash, but got: {:?}",
+            data
+        )),
+    }
+}
+
+/// Load a subschema that may not have a `type`, e.g. `if: {required: [kind]}`, whose
+/// type-specific keywords then only apply to values of their type
+fn load_schema_without_type(value: &saphyr::MarkedYaml) -> Result<YamlSchema> {
+    match &value.data {
+        saphyr::YamlData::Hash(hash) => {
+            let (annotations, hash) = load_annotations(hash)?;
+            Ok(with_annotations(
+                annotations,
+                construct_without_type(&hash)?,
+            ))
+        }
+        _ => load_schema(value),
     }
 }
 
 impl Constructor<AnyOfSchema> for AnyOfSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<AnyOfSchema> {
+    fn construct(hash: &MarkedHash) -> Result<AnyOfSchema> {
         let mut any_of_schema = AnyOfSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "anyOf" => {
-                        any_of_schema.any_of = load_array_of_schemas(value)?;
+                        any_of_schema.any_of = load_array_of_schemas(value, "anyOf")?;
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "an `anyOf`")),
                 }
             }
         }
@@ -392,15 +1227,15 @@ impl Constructor<AnyOfSchema> for AnyOfSchema {
 }
 
 impl Constructor<OneOfSchema> for OneOfSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<OneOfSchema> {
+    fn construct(hash: &MarkedHash) -> Result<OneOfSchema> {
         let mut one_of_schema = OneOfSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "oneOf" => {
-                        one_of_schema.one_of = load_array_of_schemas(value)?;
+                        one_of_schema.one_of = load_array_of_schemas(value, "oneOf")?;
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `oneOf`")),
                 }
             }
         }
@@ -408,90 +1243,203 @@ impl Constructor<OneOfSchema> for OneOfSchema {
     }
 }
 
-fn load_properties(hash: &saphyr::Hash) -> Result<HashMap<String, YamlSchema>> {
+impl Constructor<RefSchema> for RefSchema {
+    fn construct(hash: &MarkedHash) -> Result<RefSchema> {
+        let mut ref_schema = RefSchema::default();
+        for (key, value) in hash.iter() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
+                    "$ref" => {
+                        ref_schema.r#ref = load_string_value(value)?;
+                    }
+                    _ => return Err(unsupported_keyword(key, "a `$ref`")),
+                }
+            }
+        }
+        Ok(ref_schema)
+    }
+}
+
+fn load_properties(hash: &MarkedHash) -> Result<HashMap<String, YamlSchema>> {
     let mut properties = HashMap::new();
     for (key, value) in hash.iter() {
-        if let saphyr::Yaml::String(key) = key {
-            let schema = YamlSchema::construct(value.as_hash().unwrap())?;
+        if let saphyr::YamlData::String(key) = &key.data {
+            let schema = load_schema(value)?;
             properties.insert(key.clone(), schema);
         } else {
-            return Err(unsupported_type!(
+            return Err(schema_error!(
+                key,
                 "Expected a string key, but got: {:?}",
-                key
+                key.data
             ));
         }
     }
     Ok(properties)
 }
 
-fn load_additional_properties(value: &saphyr::Yaml) -> Result<BoolOrTypedSchema> {
-    match value {
-        saphyr::Yaml::Boolean(b) => Ok(BoolOrTypedSchema::Boolean(*b)),
-        saphyr::Yaml::Hash(hash) => {
-            let schema = TypedSchema::construct(hash)?;
-            Ok(BoolOrTypedSchema::TypedSchema(Box::new(schema)))
+/// Load `items` or `additionalProperties`, which is either a boolean, a `$ref`, or a schema
+/// with a `type`
+fn load_bool_or_typed_schema(
+    value: &saphyr::MarkedYaml,
+    keyword: &str,
+) -> Result<BoolOrTypedSchema> {
+    match &value.data {
+        saphyr::YamlData::Boolean(b) => Ok(BoolOrTypedSchema::Boolean(*b)),
+        saphyr::YamlData::Hash(hash) => {
+            let (annotations, hash) = load_annotations(hash)?;
+            if !hash.contains_key(&sys("$ref")) && !hash.contains_key(&sys("type")) {
+                return Err(schema_error!(
+                    value,
+                    "{}: Expected a schema with a type or a $ref",
+                    keyword
+                ));
+            }
+            // A `$ref` with other keywords alongside it is constructed like any other schema
+            let has_siblings = hash.contains_key(&sys("$ref")) && hash.len() > 1;
+            if !annotations.is_empty() || has_siblings {
+                Ok(BoolOrTypedSchema::Annotated(AnnotatedSchema {
+                    annotations,
+                    schema: Box::new(construct_schema(&hash)?),
+                }))
+            } else if hash.contains_key(&sys("$ref")) {
+                Ok(BoolOrTypedSchema::Reference(RefSchema::construct(&hash)?))
+            } else {
+                let schema = TypedSchema::construct(&hash)?;
+                Ok(BoolOrTypedSchema::TypedSchema(Box::new(schema)))
+            }
         }
-        _ => Err(unsupported_type!(
-            "Expected type: boolean or hash, but got: {:?}",
-            value
+        data => Err(schema_error!(
+            value,
+            "{}: Expected type: boolean or hash, but got: {:?}",
+            keyword,
+            data
         )),
     }
 }
 
+/// Split the annotation keywords (e.g. `title` and `description`) off a schema hash, returning
+/// the annotations and the remaining keywords
+fn load_annotations(hash: &MarkedHash) -> Result<(Annotations, MarkedHash)> {
+    let mut annotations = Annotations::default();
+    let mut other_keywords = MarkedHash::new();
+    for (key, value) in hash.iter() {
+        match key.data.as_str() {
+            Some("title") => annotations.title = Some(load_string_value(value)?),
+            Some("description") => annotations.description = Some(load_string_value(value)?),
+            Some("$comment") => annotations.comment = Some(load_string_value(value)?),
+            Some("default") => annotations.default = Some(value.clone()),
+            Some("examples") => match &value.data {
+                saphyr::YamlData::Array(examples) => annotations.examples = Some(examples.clone()),
+                data => {
+                    return Err(schema_error!(
+                        value,
+                        "examples: Expected an array, but got: {:?}",
+                        data
+                    ))
+                }
+            },
+            Some("deprecated") => annotations.deprecated = load_boolean(value)?,
+            Some("readOnly") => annotations.read_only = load_boolean(value)?,
+            Some("writeOnly") => annotations.write_only = load_boolean(value)?,
+            _ => {
+                other_keywords.insert(key.clone(), value.clone());
+            }
+        }
+    }
+    Ok((annotations, other_keywords))
+}
+
 impl Constructor<NotSchema> for NotSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<NotSchema> {
+    fn construct(hash: &MarkedHash) -> Result<NotSchema> {
+        let mut not = None;
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "not" => {
-                        let schema = YamlSchema::construct(value.as_hash().unwrap())?;
-                        return Ok(NotSchema {
-                            not: Box::new(schema),
-                        });
+                        not = Some(load_schema(value)?);
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `not`")),
                 }
             }
         }
-        unimplemented!()
+        match not {
+            Some(schema) => Ok(NotSchema {
+                not: Box::new(schema),
+            }),
+            None => Err(generic_error!("No not key found in hash")),
+        }
     }
 }
 
-fn load_integer(value: &saphyr::Yaml) -> Result<i64> {
-    match value {
-        saphyr::Yaml::Integer(i) => Ok(*i),
-        _ => Err(unsupported_type!(
+fn load_integer(value: &saphyr::MarkedYaml) -> Result<i64> {
+    match &value.data {
+        saphyr::YamlData::Integer(i) => Ok(*i),
+        data => Err(schema_error!(
+            value,
             "Expected type: integer, but got: {:?}",
-            value
+            data
+        )),
+    }
+}
+
+/// Load a count, e.g. `minItems` or `maxLength`, which must be a non-negative integer
+fn load_count(value: &saphyr::MarkedYaml) -> Result<usize> {
+    let i = load_integer(value)?;
+    usize::try_from(i)
+        .map_err(|_| schema_error!(value, "Expected a non-negative integer, but got: {}", i))
+}
+
+/// Load the value of `multipleOf`, which must be greater than 0
+fn load_multiple_of(value: &saphyr::MarkedYaml) -> Result<Number> {
+    let number = load_number(value)?;
+    let is_positive = match number {
+        Number::Integer(i) => i > 0,
+        Number::Float(f) => f > 0.0,
+    };
+    if !is_positive {
+        return Err(schema_error!(
+            value,
+            "multipleOf must be greater than 0, but got: {}",
+            number
+        ));
+    }
+    Ok(number)
+}
+
+fn load_boolean(value: &saphyr::MarkedYaml) -> Result<bool> {
+    match &value.data {
+        saphyr::YamlData::Boolean(b) => Ok(*b),
+        data => Err(schema_error!(
+            value,
+            "Expected type: boolean, but got: {:?}",
+            data
         )),
     }
 }
 
-fn load_number(value: &saphyr::Yaml) -> Result<Number> {
-    match value {
-        saphyr::Yaml::Integer(i) => Ok(Number::integer(*i)),
-        saphyr::Yaml::Real(f) => Ok(Number::float(f.parse::<f64>()?)),
-        _ => Err(unsupported_type!(
+fn load_number(value: &saphyr::MarkedYaml) -> Result<Number> {
+    match &value.data {
+        saphyr::YamlData::Integer(i) => Ok(Number::integer(*i)),
+        saphyr::YamlData::Real(f) => match f.parse::<f64>() {
+            Ok(f) => Ok(Number::float(f)),
+            Err(e) => Err(schema_error!(value, "Invalid float {}: {}", f, e)),
+        },
+        data => Err(schema_error!(
+            value,
             "Expected type: integer or float, but got: {:?}",
-            value
+            data
         )),
     }
 }
 
 impl Constructor<NumberSchema> for NumberSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<NumberSchema> {
+    fn construct(hash: &MarkedHash) -> Result<NumberSchema> {
         let mut number_schema = NumberSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "minimum" => {
-                        let minimum = load_number(value).map_err(|_| {
-                            crate::Error::UnsupportedType(format!(
-                                "Expected type: integer or float, but got: {:?}",
-                                &value
-                            ))
-                        })?;
-                        number_schema.minimum = Some(minimum);
+                        number_schema.minimum = Some(load_number(value)?);
                     }
                     "maximum" => {
                         number_schema.maximum = Some(load_number(value)?);
@@ -503,15 +1451,19 @@ impl Constructor<NumberSchema> for NumberSchema {
                         number_schema.exclusive_maximum = Some(load_number(value)?);
                     }
                     "multipleOf" => {
-                        number_schema.multiple_of = Some(load_number(value)?);
+                        number_schema.multiple_of = Some(load_multiple_of(value)?);
                     }
                     "type" => {
                         let s = load_string_value(value)?;
                         if s != "number" {
-                            return Err(unsupported_type!("Expected type: number, but got: {}", s));
+                            return Err(schema_error!(
+                                value,
+                                "Expected type: number, but got: {}",
+                                s
+                            ));
                         }
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `type: number`")),
                 }
             }
         }
@@ -520,49 +1472,45 @@ impl Constructor<NumberSchema> for NumberSchema {
 }
 
 impl Constructor<StringSchema> for StringSchema {
-    fn construct(hash: &saphyr::Hash) -> Result<StringSchema> {
+    fn construct(hash: &MarkedHash) -> Result<StringSchema> {
         let mut string_schema = StringSchema::default();
         for (key, value) in hash.iter() {
-            if let saphyr::Yaml::String(key) = key {
-                match key.as_str() {
+            if let saphyr::YamlData::String(keyword) = &key.data {
+                match keyword.as_str() {
                     "minLength" => {
-                        if let saphyr::Yaml::Integer(i) = value {
-                            string_schema.min_length = Some(*i as usize);
-                        } else {
-                            return Err(unsupported_type!(
-                                "minLength expected integer, but got: {:?}",
-                                value
-                            ));
-                        }
+                        string_schema.min_length = Some(load_count(value)?);
                     }
                     "maxLength" => {
-                        if let saphyr::Yaml::Integer(i) = value {
-                            string_schema.max_length = Some(*i as usize);
-                        } else {
-                            return Err(unsupported_type!(
-                                "maxLength expected integer, but got: {:?}",
-                                value
-                            ));
-                        }
+                        string_schema.max_length = Some(load_count(value)?);
                     }
                     "pattern" => {
-                        if let saphyr::Yaml::String(s) = value {
-                            let regex = regex::Regex::new(s.as_str())?;
+                        if let saphyr::YamlData::String(s) = &value.data {
+                            let regex = regex::Regex::new(s.as_str()).map_err(|e| {
+                                schema_error!(value, "Invalid regular expression pattern: {}", e)
+                            })?;
                             string_schema.pattern = Some(regex);
                         } else {
-                            return Err(unsupported_type!(
+                            return Err(schema_error!(
+                                value,
                                 "pattern expected string, but got: {:?}",
-                                value
+                                value.data
                             ));
                         }
                     }
+                    "format" => {
+                        string_schema.format = Some(load_string_value(value)?);
+                    }
                     "type" => {
                         let s = load_string_value(value)?;
                         if s != "string" {
-                            return Err(unsupported_type!("Expected type: string, but got: {}", s));
+                            return Err(schema_error!(
+                                value,
+                                "Expected type: string, but got: {}",
+                                s
+                            ));
                         }
                     }
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_keyword(key, "a `type: string`")),
                 }
             }
         }
@@ -570,18 +1518,8 @@ impl Constructor<StringSchema> for StringSchema {
     }
 }
 
-fn load_array_items(value: &saphyr::Yaml) -> Result<BoolOrTypedSchema> {
-    match value {
-        saphyr::Yaml::Boolean(b) => Ok(BoolOrTypedSchema::Boolean(*b)),
-        saphyr::Yaml::Hash(hash) => Ok(BoolOrTypedSchema::TypedSchema(Box::new(
-            TypedSchema::construct(hash)?,
-        ))),
-        _ => unimplemented!(),
-    }
-}
-
-fn load_enum_values(values: &[saphyr::Yaml]) -> Result<Vec<ConstValue>> {
-    Ok(values.iter().map(ConstValue::from_saphyr_yaml).collect())
+fn load_enum_values(values: &[saphyr::MarkedYaml]) -> Result<Vec<ConstValue>> {
+    values.iter().map(ConstValue::from_saphyr_yaml).collect()
 }
 
 /// Convert a Loader to a RootSchema
@@ -591,21 +1529,26 @@ impl From<RootLoader> for RootSchema {
         RootSchema {
             id: loader.id,
             meta_schema: loader.meta_schema,
+            location: None,
+            defs: loader.defs,
+            external_schemas: HashMap::new(),
             schema: Rc::new(loader.schema.unwrap_or(YamlSchema::Empty)),
+            warnings: Vec::new(),
         }
     }
 }
 
-fn yaml_to_string(yaml: &saphyr::Yaml, msg: &str) -> Result<String> {
-    match yaml {
-        saphyr::Yaml::String(s) => Ok(s.clone()),
-        _ => Err(unsupported_type!("Expected a string, but got: {:?}", msg)),
+fn yaml_to_string(yaml: &saphyr::MarkedYaml, msg: &str) -> Result<String> {
+    match &yaml.data {
+        saphyr::YamlData::String(s) => Ok(s.clone()),
+        data => Err(schema_error!(yaml, "{}, but got: {:?}", msg, data)),
     }
 }
 
-/// Convenience function to create saphyr::Yaml::String from a &str
-fn sys(str: &str) -> saphyr::Yaml {
-    saphyr::Yaml::String(String::from(str))
+/// Convenience function to create a string saphyr::MarkedYaml from a &str, e.g. to look up
+/// a key in a hash. Keys are compared without their positions.
+fn sys(str: &str) -> saphyr::MarkedYaml {
+    saphyr::MarkedYaml::from(saphyr::YamlData::String(String::from(str)))
 }
 
 #[cfg(test)]
@@ -634,7 +1577,7 @@ mod tests {
 
     #[test]
     fn test_const_string() {
-        let docs = saphyr::Yaml::load_from_str("const: string value").unwrap();
+        let docs = saphyr::MarkedYaml::load_from_str("const: string value").unwrap();
         let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
         let const_schema = ConstSchema {
             r#const: ConstValue::string("string value"),
@@ -647,7 +1590,7 @@ mod tests {
 
     #[test]
     fn test_const_integer() {
-        let docs = saphyr::Yaml::load_from_str("const: 42").unwrap();
+        let docs = saphyr::MarkedYaml::load_from_str("const: 42").unwrap();
         let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
         let const_schema = ConstSchema {
             r#const: ConstValue::integer(42),
@@ -660,18 +1603,179 @@ mod tests {
 
     #[test]
     fn test_type_foo_should_error() {
-        let docs = saphyr::Yaml::load_from_str("type: foo").unwrap();
+        let docs = saphyr::MarkedYaml::load_from_str("type: foo").unwrap();
         let root_schema = load_from_doc(docs.first().unwrap());
         assert!(root_schema.is_err());
         assert_eq!(
             root_schema.unwrap_err().to_string(),
-            "Unsupported type 'foo'!"
+            "[1:7] Unsupported type 'foo'!"
+        );
+    }
+
+    #[test]
+    fn test_invalid_schemas_should_error_with_positions() {
+        let cases = [
+            ("type: object\nproperties: 42\n", 2, 13),
+            ("type: object\nrequired: [name, 42]\n", 2, 18),
+            ("type: array\nitems: 42\n", 2, 8),
+            ("allOf:\n  - type: string\nfoo: bar\n", 3, 1),
+            ("enum: [a, [b]]\n", 1, 11),
+            ("not: 42\n", 1, 6),
+            ("- type: string\n", 1, 1),
+            ("type: array\nminItems: -1\n", 2, 11),
+            ("type: object\nmaxProperties: -1\n", 2, 16),
+            ("type: string\nminLength: -1\n", 2, 12),
+            ("type: integer\nmultipleOf: 0\n", 2, 13),
+            ("type: number\nmultipleOf: -0.5\n", 2, 13),
+        ];
+        for (schema, line, col) in cases {
+            match load_from_str(schema) {
+                Err(Error::InvalidSchema {
+                    line: l, col: c, ..
+                }) => assert_eq!((l, c), (line, col), "{}", schema),
+                result => panic!(
+                    "Expected an InvalidSchema error for {}: {:?}",
+                    schema, result
+                ),
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_keywords() {
+        let schema = "type: string\nx-order: 1\nminLenght: 1\n";
+        let result = load_from_str(schema);
+        assert!(matches!(
+            result,
+            Err(Error::InvalidSchema {
+                line: 3,
+                col: 1,
+                ..
+            })
+        ));
+
+        let options = LoaderOptions {
+            unknown_keywords: UnknownKeywords::Warn,
+            ..Default::default()
+        };
+        let root_schema = load_from_str_with_options(schema, &options).unwrap();
+        assert_eq!(
+            *root_schema.schema.as_ref(),
+            YamlSchema::String(StringSchema::default())
+        );
+        assert_eq!(
+            root_schema.warnings,
+            vec![LoaderWarning {
+                line: 3,
+                col: 1,
+                message: "Unknown keyword 'minLenght' is ignored".to_string(),
+            }]
+        );
+
+        let options = LoaderOptions {
+            unknown_keywords: UnknownKeywords::Ignore,
+            allow_extensions: false,
+        };
+        let root_schema = load_from_str_with_options(schema, &options).unwrap();
+        assert!(root_schema.warnings.is_empty());
+
+        let options = LoaderOptions {
+            unknown_keywords: UnknownKeywords::Warn,
+            allow_extensions: false,
+        };
+        let root_schema = load_from_str_with_options(schema, &options).unwrap();
+        assert_eq!(root_schema.warnings.len(), 2);
+
+        // A keyword for another type is only an error in strict mode
+        let schema = "type: integer\nminLength: 3\n";
+        assert!(matches!(
+            load_from_str(schema),
+            Err(Error::InvalidSchema { line: 2, .. })
+        ));
+        let options = LoaderOptions {
+            unknown_keywords: UnknownKeywords::Warn,
+            ..Default::default()
+        };
+        let root_schema = load_from_str_with_options(schema, &options).unwrap();
+        assert_eq!(
+            *root_schema.schema.as_ref(),
+            YamlSchema::Integer(IntegerSchema::default())
+        );
+        assert_eq!(
+            root_schema.warnings[0].to_string(),
+            "[2:1] Keyword 'minLength' doesn't apply to a `type: integer` schema and is ignored"
         );
     }
 
+    #[test]
+    fn test_annotations() {
+        let schema = r#"
+            title: Server
+            type: object
+            properties:
+              host:
+                description: The host name
+                $comment: Kept for older clients
+                deprecated: true
+                readOnly: true
+                type: string
+                default: localhost
+                examples: [localhost, example.com]
+            additionalProperties:
+              description: Any other setting
+              type: string
+        "#;
+        let root_schema = load_from_str(schema).unwrap();
+        let YamlSchema::Annotated(annotated_schema) = root_schema.schema.as_ref() else {
+            panic!("Expected an annotated schema, got: {}", root_schema.schema);
+        };
+        assert_eq!(
+            annotated_schema.annotations.title.as_deref(),
+            Some("Server")
+        );
+        let YamlSchema::Object(object_schema) = annotated_schema.schema.as_ref() else {
+            panic!("Expected an object schema");
+        };
+        let properties = object_schema.properties.as_ref().unwrap();
+        let YamlSchema::Annotated(host) = properties.get("host").unwrap() else {
+            panic!("Expected an annotated schema");
+        };
+        assert_eq!(
+            host.annotations.description.as_deref(),
+            Some("The host name")
+        );
+        assert_eq!(
+            host.annotations.comment.as_deref(),
+            Some("Kept for older clients")
+        );
+        assert!(host.annotations.deprecated);
+        assert!(host.annotations.read_only);
+        assert!(!host.annotations.write_only);
+        assert_eq!(
+            host.annotations.default.as_ref().unwrap().data.as_str(),
+            Some("localhost")
+        );
+        assert_eq!(host.annotations.examples.as_ref().unwrap().len(), 2);
+        assert_eq!(*host.schema, YamlSchema::String(StringSchema::default()));
+        assert!(matches!(
+            object_schema.additional_properties,
+            Some(BoolOrTypedSchema::Annotated(_))
+        ));
+
+        let result = load_from_str("type: string\ndeprecated: yes\n");
+        assert!(matches!(
+            result,
+            Err(Error::InvalidSchema {
+                line: 2,
+                col: 13,
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn test_type_string() {
-        let docs = saphyr::Yaml::load_from_str("type: string").unwrap();
+        let docs = saphyr::MarkedYaml::load_from_str("type: string").unwrap();
         let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
         let string_schema = StringSchema::default();
         assert_eq!(
@@ -682,7 +1786,7 @@ mod tests {
 
     #[test]
     fn test_type_string_with_pattern() {
-        let docs = saphyr::Yaml::load_from_str(
+        let docs = saphyr::MarkedYaml::load_from_str(
             r#"
         type: string
         pattern: "^(\\([0-9]{3}\\))?[0-9]{3}-[0-9]{4}$"
@@ -702,23 +1806,25 @@ mod tests {
 
     #[test]
     fn test_array_constructor_items_true() {
-        let mut hash = saphyr::Hash::new();
+        let mut hash = MarkedHash::new();
         hash.insert(sys("type"), sys("array"));
-        hash.insert(sys("items"), saphyr::Yaml::Boolean(true));
+        hash.insert(
+            sys("items"),
+            saphyr::MarkedYaml::from(saphyr::YamlData::Boolean(true)),
+        );
         let array_schema = ArraySchema::construct(&hash).unwrap();
         assert_eq!(
             array_schema,
             ArraySchema {
                 items: Some(BoolOrTypedSchema::Boolean(true)),
-                prefix_items: None,
-                contains: None
+                ..Default::default()
             }
         );
     }
 
     #[test]
     fn test_integer_schema() {
-        let docs = saphyr::Yaml::load_from_str("type: integer").unwrap();
+        let docs = saphyr::MarkedYaml::load_from_str("type: integer").unwrap();
         let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
         let integer_schema = IntegerSchema::default();
         assert_eq!(
@@ -729,7 +1835,7 @@ mod tests {
 
     #[test]
     fn test_enum() {
-        let docs = saphyr::Yaml::load_from_str(
+        let docs = saphyr::MarkedYaml::load_from_str(
             r#"
         enum:
           - foo
@@ -751,7 +1857,7 @@ mod tests {
 
     #[test]
     fn test_enum_without_type() {
-        let docs = saphyr::Yaml::load_from_str(
+        let docs = saphyr::MarkedYaml::load_from_str(
             r#"
             enum:
               - red
@@ -775,4 +1881,128 @@ mod tests {
         };
         assert_eq!(*root_schema.schema.as_ref(), YamlSchema::Enum(enum_schema));
     }
+
+    #[test]
+    fn test_defs_and_ref() {
+        let docs = saphyr::MarkedYaml::load_from_str(
+            r##"
+            $defs:
+              name:
+                type: string
+            definitions:
+              age:
+                type: integer
+            $ref: "#/$defs/name"
+            "##,
+        )
+        .unwrap();
+        let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
+        assert_eq!(
+            *root_schema.schema.as_ref(),
+            YamlSchema::Ref(RefSchema::new("#/$defs/name"))
+        );
+        assert_eq!(
+            root_schema.resolve("#/$defs/name"),
+            Some(&YamlSchema::String(StringSchema::default()))
+        );
+        assert_eq!(
+            root_schema.resolve("#/definitions/age"),
+            Some(&YamlSchema::Integer(IntegerSchema::default()))
+        );
+        assert_eq!(root_schema.resolve("#/$defs/missing"), None);
+    }
+
+    #[test]
+    fn test_load_file_with_external_refs() {
+        let root_schema = load_file("tests/fixtures/refs/server.yaml").unwrap();
+        // network.yaml is referenced by both server.yaml and upstream.yaml, but only loaded once
+        assert_eq!(root_schema.external_schemas.len(), 2);
+        let network = root_schema
+            .external_schemas
+            .values()
+            .find(|schema| schema.id.is_some())
+            .unwrap();
+        let location = network.location.as_ref().unwrap();
+        assert!(location.ends_with("network.yaml"));
+        assert_eq!(
+            root_schema.resolve(&format!("{}#/$defs/host", location)),
+            Some(&YamlSchema::String(StringSchema::default()))
+        );
+    }
+
+    #[test]
+    fn test_refs_in_data_are_not_followed() {
+        let result = load_from_str(
+            r#"
+            type: object
+            properties:
+              link:
+                type: object
+                default:
+                  $ref: ./nope.yaml
+                examples:
+                  - $ref: ./nope.yaml#/$defs/nope
+            "#,
+        );
+        assert!(result.is_ok(), "{:?}", result.err());
+    }
+
+    #[test]
+    fn test_invalid_external_schema_should_error_with_its_location() {
+        let result = load_file("tests/fixtures/refs/invalid/root.yaml");
+        let Err(Error::InvalidSchema {
+            location: Some(location),
+            line: 2,
+            col: 12,
+            ..
+        }) = result
+        else {
+            panic!("Expected an InvalidSchema error in bad.yaml: {:?}", result);
+        };
+        assert!(location.ends_with("bad.yaml"));
+    }
+
+    #[test]
+    fn test_load_file_with_missing_external_ref() {
+        let result = load_from_str("$ref: tests/fixtures/refs/missing.yaml");
+        assert!(matches!(result, Err(Error::FileNotFound(_))));
+    }
+
+    #[test]
+    fn test_if_then_with_other_keywords() {
+        let docs = saphyr::MarkedYaml::load_from_str(
+            r#"
+            type: string
+            if:
+              type: string
+              maxLength: 3
+            then:
+              type: string
+              pattern: "^[A-Z]+$"
+            "#,
+        )
+        .unwrap();
+        let root_schema = load_from_doc(docs.first().unwrap()).unwrap();
+        let if_then_else_schema = IfThenElseSchema {
+            r#if: Box::new(YamlSchema::String(StringSchema {
+                max_length: Some(3),
+                ..Default::default()
+            })),
+            then: Some(Box::new(YamlSchema::String(StringSchema {
+                pattern: Some(Regex::new("^[A-Z]+$").unwrap()),
+                ..Default::default()
+            }))),
+            r#else: None,
+        };
+        assert_eq!(
+            *root_schema.schema.as_ref(),
+            YamlSchema::AllOf(AllOfSchema {
+                all_of: vec![
+                    YamlSchema::String(StringSchema::default()),
+                    YamlSchema::IfThenElse(if_then_else_schema)
+                ],
+                implicit: true,
+            })
+        );
+    }
 }
@@ -0,0 +1,236 @@
+/// The YAML Schema meta-schema, and checking schema documents against it
+use crate::validation::ValidationError;
+use crate::validation::ValidationErrorKind;
+use crate::Context;
+use crate::Engine;
+use crate::EngineOptions;
+use crate::Result;
+use crate::RootSchema;
+
+/// The meta-schema, which describes the schemas that yaml-schema can load
+pub const META_SCHEMA: &str = include_str!("../yaml-schema.yaml");
+
+/// The `$schema` URIs of JSON Schema 2020-12, which the meta-schema is based on
+const DRAFT_2020_12: &[&str] = &[
+    "https://yaml-schema.net/draft/2020-12/schema",
+    "https://json-schema.org/draft/2020-12/schema",
+];
+
+/// The `$schema` URIs of JSON Schema draft-07
+const DRAFT_07: &[&str] = &[
+    "http://json-schema.org/draft-07/schema#",
+    "http://json-schema.org/draft-07/schema",
+    "https://json-schema.org/draft-07/schema#",
+    "https://json-schema.org/draft-07/schema",
+];
+
+/// The keywords that were added after draft-07
+const NOT_IN_DRAFT_07: &[&str] = &[
+    "$defs",
+    "dependentRequired",
+    "dependentSchemas",
+    "prefixItems",
+    "minContains",
+    "maxContains",
+    "deprecated",
+];
+
+/// Check a schema document against the meta-schema, and against the draft named in its
+/// `$schema`, if any. Unlike loading the schema, this reports every problem, each with the
+/// line and column it was found at.
+pub fn check_schema(schema: &str) -> Result<Vec<ValidationError>> {
+    let docs = saphyr::MarkedYaml::load_from_str(schema)?;
+    // An empty document is an empty schema, which accepts everything
+    let Some(doc) = docs.first() else {
+        return Ok(vec![]);
+    };
+    let meta_schema = RootSchema::load_from_str(META_SCHEMA)?;
+    let options = EngineOptions {
+        format_assertion: true,
+        ..Default::default()
+    };
+    let context = Engine::evaluate_with_options(&meta_schema, schema, &options)?;
+    let mut errors = context.errors.take();
+    errors.extend(draft_errors(doc));
+    Ok(errors)
+}
+
+/// Check the schema against the draft named in its `$schema`
+fn draft_errors(doc: &saphyr::MarkedYaml) -> Vec<ValidationError> {
+    let context = Context::new(false);
+    let Some(hash) = doc.data.as_hash() else {
+        return vec![];
+    };
+    let meta_schema = hash
+        .iter()
+        .find(|(key, _)| key.data.as_str() == Some("$schema"));
+    if let Some((_, value)) = meta_schema {
+        // A `$schema` that isn't a string is reported by the meta-schema
+        if let Some(uri) = value.data.as_str() {
+            if DRAFT_07.contains(&uri) {
+                check_keywords(&context, doc, NOT_IN_DRAFT_07, "draft-07");
+            } else if !DRAFT_2020_12.contains(&uri) {
+                context.append_path("$schema").add_error(
+                    value,
+                    ValidationErrorKind::UnknownMetaSchema {
+                        meta_schema: uri.to_string(),
+                    },
+                    format!(
+                        "Unknown $schema: {}, expected JSON Schema 2020-12 or draft-07",
+                        uri
+                    ),
+                );
+            }
+        }
+    }
+    context.errors.take()
+}
+
+/// Report the keywords of a schema, and of its subschemas, that aren't part of the draft
+fn check_keywords(
+    context: &Context,
+    schema: &saphyr::MarkedYaml,
+    not_in_draft: &[&str],
+    draft: &str,
+) {
+    let Some(hash) = schema.data.as_hash() else {
+        return;
+    };
+    for (key, value) in hash {
+        let Some(keyword) = key.data.as_str() else {
+            continue;
+        };
+        let context = context.append_path(keyword);
+        if not_in_draft.contains(&keyword) {
+            context.add_error(
+                key,
+                ValidationErrorKind::KeywordNotInDraft {
+                    keyword: keyword.to_string(),
+                    draft: draft.to_string(),
+                },
+                format!("`{}` is not a keyword in {}", keyword, draft),
+            );
+        }
+        match keyword {
+            "properties" | "patternProperties" | "$defs" | "definitions" | "dependentSchemas"
+            | "dependencies" => {
+                if let Some(subschemas) = value.data.as_hash() {
+                    for (name, subschema) in subschemas {
+                        let name = name.data.as_str().unwrap_or_default();
+                        check_keywords(&context.append_path(name), subschema, not_in_draft, draft);
+                    }
+                }
+            }
+            "allOf" | "anyOf" | "oneOf" | "prefixItems" => {
+                if let Some(subschemas) = value.data.as_vec() {
+                    for (index, subschema) in subschemas.iter().enumerate() {
+                        check_keywords(
+                            &context.append_path(index.to_string()),
+                            subschema,
+                            not_in_draft,
+                            draft,
+                        );
+                    }
+                }
+            }
+            "items"
+            | "additionalProperties"
+            | "contains"
+            | "propertyNames"
+            | "not"
+            | "if"
+            | "then"
+            | "else" => check_keywords(&context, value, not_in_draft, draft),
+            _ => (),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(schema: &str) -> Vec<String> {
+        check_schema(schema)
+            .unwrap()
+            .iter()
+            .map(|error| error.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_meta_schema_is_valid() {
+        assert!(check(META_SCHEMA).is_empty(), "{:?}", check(META_SCHEMA));
+        assert!(check("").is_empty());
+        assert!(check("true").is_empty());
+    }
+
+    #[test]
+    fn test_check_schema() {
+        let errors = check(
+            r#"
+type: object
+properties:
+  name:
+    type: strin
+    minLength: -1
+  port:
+    type: integer
+    maximum: high
+  tags:
+    x-ui: hidden
+    itemz:
+      type: string
+required: name
+"#,
+        );
+        assert_eq!(
+            errors,
+            vec![
+                "[5:11] .properties.name.type: None of the schemas in `anyOf` matched! (/$ref/properties/properties/$ref/additionalProperties/$ref/properties/type/anyOf)",
+                "[6:16] .properties.name.minLength: Number is too small! (/$ref/properties/properties/$ref/additionalProperties/$ref/properties/minLength/$ref/minimum)",
+                "[9:14] .properties.port.maximum: Expected a number, but got: String(\"high\") (/$ref/properties/properties/$ref/additionalProperties/$ref/properties/maximum/type)",
+                "[13:7] .properties.tags: Additional property 'itemz' is not allowed! (/$ref/properties/properties/$ref/additionalProperties/$ref/additionalProperties)",
+                "[14:11] .required: Expected an array, but got: String(\"name\") (/$ref/properties/required/$ref/type)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_schema_property_names() {
+        let schema = "type: object\npropertyNames:\n  pattern: ^[a-z]+$\n";
+        assert!(check(schema).is_empty());
+        assert!(RootSchema::load_from_str(schema).is_ok());
+        let errors = check("type: object\npropertyNames:\n  maxLength: 8\n");
+        assert_eq!(
+            errors,
+            vec![
+                "[3:14] .propertyNames: Additional property 'maxLength' is not allowed! (/$ref/properties/propertyNames/additionalProperties)",
+                "[3:3] .propertyNames: Required property 'pattern' is missing! (/$ref/properties/propertyNames/required)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_schema_draft() {
+        let errors = check(
+            r#"
+$schema: http://json-schema.org/draft-07/schema#
+type: object
+properties:
+  $defs:
+    prefixItems:
+      - type: string
+"#,
+        );
+        assert_eq!(
+            errors,
+            vec!["[6:5] .properties.$defs.prefixItems: `prefixItems` is not a keyword in draft-07"]
+        );
+        let errors = check("$schema: https://example.com/my-draft\n");
+        assert_eq!(
+            errors,
+            vec!["[1:10] .$schema: Unknown $schema: https://example.com/my-draft, expected JSON Schema 2020-12 or draft-07 (/$schema)"]
+        );
+    }
+}
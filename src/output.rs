@@ -0,0 +1,308 @@
+/// The JSON Schema 2020-12 output formats, which report validation results in a structure that
+/// other tools understand
+use serde::Serialize;
+
+use crate::validation::ValidationError;
+use crate::Context;
+
+/// The JSON Schema output formats
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    /// Only whether the document is valid
+    Flag,
+    /// A flat list of errors
+    Basic,
+    /// Errors nested by the structure of the schema, where schemas with a single error are
+    /// collapsed into that error
+    Detailed,
+    /// Errors nested by the structure of the schema, without collapsing
+    Verbose,
+}
+
+/// An output unit, as defined by the JSON Schema output formats
+///
+/// Only failed evaluations are recorded during validation, so the `verbose` format does not
+/// include the subschemas that passed.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputUnit {
+    pub valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keyword_location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance_location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<OutputUnit>,
+}
+
+impl OutputUnit {
+    /// Build the output for the given validation errors in the given format
+    pub fn from_errors(errors: &[ValidationError], format: OutputFormat) -> OutputUnit {
+        if errors.is_empty() {
+            return OutputUnit::node(true, None, None, vec![]);
+        }
+        match format {
+            OutputFormat::Flag => OutputUnit::node(false, None, None, vec![]),
+            OutputFormat::Basic => OutputUnit::node(
+                false,
+                None,
+                None,
+                errors.iter().map(OutputUnit::leaf).collect(),
+            ),
+            OutputFormat::Detailed | OutputFormat::Verbose => {
+                let mut root = KeywordNode::default();
+                for error in errors {
+                    root.insert(error);
+                }
+                OutputUnit::node(
+                    false,
+                    Some(String::new()),
+                    Some(String::new()),
+                    root.contents("", format),
+                )
+            }
+        }
+    }
+
+    /// Serialize the output as pretty-printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("OutputUnit is always serializable")
+    }
+
+    fn node(
+        valid: bool,
+        keyword_location: Option<String>,
+        instance_location: Option<String>,
+        errors: Vec<OutputUnit>,
+    ) -> OutputUnit {
+        OutputUnit {
+            valid,
+            keyword_location,
+            instance_location,
+            error: None,
+            errors,
+        }
+    }
+
+    fn leaf(error: &ValidationError) -> OutputUnit {
+        OutputUnit {
+            valid: false,
+            keyword_location: Some(error.keyword_location.clone()),
+            instance_location: Some(error.instance_location.clone()),
+            error: Some(error.error.clone()),
+            errors: vec![],
+        }
+    }
+}
+
+impl Context<'_> {
+    /// Returns the validation results in the given JSON Schema output format
+    pub fn output(&self, format: OutputFormat) -> OutputUnit {
+        OutputUnit::from_errors(&self.errors.borrow(), format)
+    }
+}
+
+/// A node in the tree of keyword locations, used to nest errors by the structure of the schema
+#[derive(Default)]
+struct KeywordNode<'e> {
+    errors: Vec<&'e ValidationError>,
+    children: Vec<(String, KeywordNode<'e>)>,
+}
+
+impl<'e> KeywordNode<'e> {
+    fn insert(&mut self, error: &'e ValidationError) {
+        let mut node = self;
+        for segment in error.keyword_location.split('/').skip(1) {
+            let index = match node.children.iter().position(|(s, _)| s == segment) {
+                Some(index) => index,
+                None => {
+                    node.children
+                        .push((segment.to_string(), KeywordNode::default()));
+                    node.children.len() - 1
+                }
+            };
+            node = &mut node.children[index].1;
+        }
+        node.errors.push(error);
+    }
+
+    /// Returns the output units for the errors of this node and of its children
+    fn contents(&self, keyword_location: &str, format: OutputFormat) -> Vec<OutputUnit> {
+        let mut units: Vec<OutputUnit> = self.errors.iter().map(|e| OutputUnit::leaf(e)).collect();
+        for (segment, child) in &self.children {
+            let child_location = format!("{}/{}", keyword_location, segment);
+            units.extend(child.units(&child_location, format));
+        }
+        units
+    }
+
+    /// Returns the output units for this node. A node with children groups their errors in a
+    /// single unit, unless the format is `detailed` and there is only one error to group.
+    fn units(&self, keyword_location: &str, format: OutputFormat) -> Vec<OutputUnit> {
+        let units = self.contents(keyword_location, format);
+        if self.children.is_empty() || (format == OutputFormat::Detailed && units.len() == 1) {
+            return units;
+        }
+        let instance_location = common_prefix(&units);
+        vec![OutputUnit::node(
+            false,
+            Some(keyword_location.to_string()),
+            Some(instance_location),
+            units,
+        )]
+    }
+}
+
+/// Returns the longest common JSON Pointer prefix of the instance locations of the units
+fn common_prefix(units: &[OutputUnit]) -> String {
+    let mut locations = units
+        .iter()
+        .filter_map(|unit| unit.instance_location.as_deref());
+    let first: Vec<&str> = match locations.next() {
+        Some(location) => location.split('/').collect(),
+        None => return String::new(),
+    };
+    let len = locations.fold(first.len(), |len, location| {
+        first
+            .iter()
+            .zip(location.split('/'))
+            .take(len)
+            .take_while(|(a, b)| *a == b)
+            .count()
+    });
+    first[..len].join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::RootSchema;
+
+    use super::*;
+
+    const SCHEMA: &str = r#"
+        type: object
+        properties:
+          server:
+            type: object
+            properties:
+              host:
+                type: string
+              port:
+                type: integer
+                maximum: 65535
+          ports:
+            type: array
+            items:
+              type: integer
+              maximum: 10
+          labels:
+            type: object
+            patternProperties:
+              "^x-":
+                type: string
+        "#;
+
+    fn output(value: &str, format: OutputFormat) -> OutputUnit {
+        let root_schema = RootSchema::load_from_str(SCHEMA).unwrap();
+        let context = Engine::evaluate(&root_schema, value, false).unwrap();
+        context.output(format)
+    }
+
+    #[test]
+    fn test_valid_output() {
+        for format in [
+            OutputFormat::Flag,
+            OutputFormat::Basic,
+            OutputFormat::Detailed,
+            OutputFormat::Verbose,
+        ] {
+            let output = output("server:\n  port: 8080", format);
+            assert_eq!(output.to_json(), "{\n  \"valid\": true\n}");
+        }
+    }
+
+    #[test]
+    fn test_flag_and_basic_output() {
+        let value = "server:\n  host: 42\n  port: 65536";
+        assert_eq!(
+            output(value, OutputFormat::Flag).to_json(),
+            "{\n  \"valid\": false\n}"
+        );
+        let basic = output(value, OutputFormat::Basic);
+        assert!(!basic.valid);
+        assert_eq!(basic.errors.len(), 2);
+        let port_error = &basic.errors[1];
+        assert_eq!(
+            port_error.keyword_location.as_deref(),
+            Some("/properties/server/properties/port/maximum")
+        );
+        assert_eq!(
+            port_error.instance_location.as_deref(),
+            Some("/server/port")
+        );
+        assert_eq!(port_error.error.as_deref(), Some("Number is too big!"));
+    }
+
+    #[test]
+    fn test_basic_output_for_items_and_pattern_properties() {
+        let value = "ports: [1, 20]\nlabels:\n  x-team: 42";
+        let basic = output(value, OutputFormat::Basic);
+        assert_eq!(basic.errors.len(), 2);
+        let item_error = &basic.errors[0];
+        assert_eq!(
+            item_error.keyword_location.as_deref(),
+            Some("/properties/ports/items/maximum")
+        );
+        assert_eq!(item_error.instance_location.as_deref(), Some("/ports/1"));
+        let label_error = &basic.errors[1];
+        assert_eq!(
+            label_error.keyword_location.as_deref(),
+            Some("/properties/labels/patternProperties/^x-/type")
+        );
+        assert_eq!(
+            label_error.instance_location.as_deref(),
+            Some("/labels/x-team")
+        );
+    }
+
+    #[test]
+    fn test_detailed_and_verbose_output() {
+        let value = "server:\n  host: 42\n  port: 65536";
+        let detailed = output(value, OutputFormat::Detailed);
+        assert_eq!(detailed.keyword_location.as_deref(), Some(""));
+        assert_eq!(detailed.errors.len(), 1);
+        let properties = &detailed.errors[0];
+        assert_eq!(
+            properties.keyword_location.as_deref(),
+            Some("/properties/server/properties")
+        );
+        assert_eq!(properties.instance_location.as_deref(), Some("/server"));
+        assert_eq!(properties.errors.len(), 2);
+        assert_eq!(
+            properties.errors[0].keyword_location.as_deref(),
+            Some("/properties/server/properties/host/type")
+        );
+
+        let verbose = output(value, OutputFormat::Verbose);
+        let mut unit = &verbose.errors[0];
+        for location in [
+            "/properties",
+            "/properties/server",
+            "/properties/server/properties",
+        ] {
+            assert_eq!(unit.keyword_location.as_deref(), Some(location));
+            unit = &unit.errors[0];
+        }
+        assert_eq!(
+            unit.keyword_location.as_deref(),
+            Some("/properties/server/properties/host")
+        );
+        assert_eq!(
+            unit.errors[0].error.as_deref(),
+            Some("Expected a string, but got: Integer(42)")
+        );
+    }
+}
@@ -0,0 +1,504 @@
+/// Reports of the validation errors found in one or more files, in the formats supported by
+/// `ys --format`
+use serde::Serialize;
+use serde_json::json;
+
+use crate::code_frame::render_code_frame;
+use crate::validation::ValidationError;
+use crate::validation::ValidationErrorKind;
+use crate::version;
+
+/// The formats that `ys` can report validation errors in
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReportFormat {
+    /// `file: [line:col] .path: message` lines
+    #[default]
+    Text,
+    /// A JSON array of errors
+    Json,
+    /// A YAML sequence of errors
+    Yaml,
+    /// A SARIF 2.1.0 log, for code scanning tools
+    Sarif,
+    /// A JUnit XML report, with a test case for each file
+    Junit,
+    /// GitHub Actions workflow commands, which annotate the errors in pull requests
+    Github,
+    /// Code frames showing the lines of each file that failed, with the values underlined
+    Pretty,
+}
+
+/// The validation errors found in a file
+#[derive(Debug)]
+pub struct FileReport {
+    pub file: String,
+    /// The contents of the file, used to show the lines that failed
+    pub source: Option<String>,
+    pub errors: Vec<ValidationError>,
+}
+
+/// The validation errors found in every validated file
+#[derive(Debug, Default)]
+pub struct Report {
+    pub files: Vec<FileReport>,
+}
+
+/// A validation error, flattened with the name of the file it was found in
+#[derive(Debug, Serialize)]
+struct ReportedError<'a> {
+    file: &'a str,
+    document: usize,
+    line: Option<usize>,
+    column: Option<usize>,
+    path: &'a str,
+    message: &'a str,
+}
+
+impl Report {
+    pub fn new() -> Report {
+        Report::default()
+    }
+
+    /// Add the validation errors found in a file. A file without errors is still reported as
+    /// having been validated.
+    pub fn add_file<S: Into<String>>(&mut self, file: S, errors: Vec<ValidationError>) {
+        self.files.push(FileReport {
+            file: file.into(),
+            source: None,
+            errors,
+        });
+    }
+
+    /// Add the validation errors found in a file, along with its contents so that the `pretty`
+    /// format can show the lines that failed
+    pub fn add_file_with_source<F: Into<String>, S: Into<String>>(
+        &mut self,
+        file: F,
+        source: S,
+        errors: Vec<ValidationError>,
+    ) {
+        self.files.push(FileReport {
+            file: file.into(),
+            source: Some(source.into()),
+            errors,
+        });
+    }
+
+    /// Add a file that couldn't be read or parsed, with a single error for the whole file
+    pub fn add_file_error<F: Into<String>, S: Into<String>>(&mut self, file: F, error: S) {
+        let error = ValidationError {
+            path: String::new(),
+            instance_location: String::new(),
+            keyword_location: String::new(),
+            document_index: 0,
+            line_col: None,
+            end_line_col: None,
+            kind: ValidationErrorKind::InvalidFile,
+            error: error.into(),
+        };
+        self.add_file(file, vec![error]);
+    }
+
+    /// Returns true if there are any errors in any file
+    pub fn has_errors(&self) -> bool {
+        self.files.iter().any(|file| !file.errors.is_empty())
+    }
+
+    /// Render the report in the given format
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Text => self
+                .files
+                .iter()
+                .flat_map(|file| {
+                    file.errors
+                        .iter()
+                        .map(|error| format!("{}: {}", file.file, error))
+                })
+                .collect::<Vec<String>>()
+                .join("\n"),
+            ReportFormat::Json => serde_json::to_string_pretty(&self.reported_errors())
+                .expect("Reported errors are always serializable"),
+            ReportFormat::Yaml => self.render_yaml(),
+            ReportFormat::Sarif => self.render_sarif(),
+            ReportFormat::Junit => self.render_junit(),
+            ReportFormat::Github => self.render_github(),
+            ReportFormat::Pretty => self.render_pretty(false),
+        }
+    }
+
+    /// Render a code frame for each error, separated by blank lines, and coloured with ANSI
+    /// escape codes if `color` is true
+    pub fn render_pretty(&self, color: bool) -> String {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                file.errors.iter().map(|error| {
+                    render_code_frame(&file.file, file.source.as_deref(), error, color)
+                })
+            })
+            .collect::<Vec<String>>()
+            .join("\n\n")
+    }
+
+    fn reported_errors(&self) -> Vec<ReportedError<'_>> {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                file.errors.iter().map(|error| ReportedError {
+                    file: &file.file,
+                    document: error.document_index,
+                    line: error.line_col.as_ref().map(|line_col| line_col.line),
+                    column: error.line_col.as_ref().map(|line_col| line_col.col),
+                    path: &error.path,
+                    message: &error.error,
+                })
+            })
+            .collect()
+    }
+
+    fn render_yaml(&self) -> String {
+        let optional = |value: Option<usize>| match value {
+            Some(value) => saphyr::Yaml::Integer(value as i64),
+            None => saphyr::Yaml::Null,
+        };
+        let errors = self
+            .reported_errors()
+            .into_iter()
+            .map(|error| {
+                let mut hash = saphyr::Hash::new();
+                for (key, value) in [
+                    ("file", saphyr::Yaml::String(error.file.to_string())),
+                    ("document", saphyr::Yaml::Integer(error.document as i64)),
+                    ("line", optional(error.line)),
+                    ("column", optional(error.column)),
+                    ("path", saphyr::Yaml::String(error.path.to_string())),
+                    ("message", saphyr::Yaml::String(error.message.to_string())),
+                ] {
+                    hash.insert(saphyr::Yaml::String(key.to_string()), value);
+                }
+                saphyr::Yaml::Hash(hash)
+            })
+            .collect();
+        let mut out = String::new();
+        saphyr::YamlEmitter::new(&mut out)
+            .dump(&saphyr::Yaml::Array(errors))
+            .expect("Writing to a String never fails");
+        out
+    }
+
+    /// Render a SARIF log with a result for each error, and a rule for each failing keyword
+    fn render_sarif(&self) -> String {
+        let mut rule_ids: Vec<&str> = Vec::new();
+        let mut results = Vec::new();
+        for file in &self.files {
+            for error in &file.errors {
+                let rule_id = rule_id(error);
+                if !rule_ids.contains(&rule_id) {
+                    rule_ids.push(rule_id);
+                }
+                let artifact_location = match file_uri(&file.file) {
+                    Some(uri) => json!({ "uri": uri }),
+                    None => json!({ "description": { "text": file.file } }),
+                };
+                let mut physical_location = json!({ "artifactLocation": artifact_location });
+                if let Some(line_col) = &error.line_col {
+                    physical_location["region"] = json!({
+                        "startLine": line_col.line,
+                        "startColumn": line_col.col
+                    });
+                }
+                results.push(json!({
+                    "ruleId": rule_id,
+                    "level": "error",
+                    "message": { "text": error.error },
+                    "locations": [{ "physicalLocation": physical_location }]
+                }));
+            }
+        }
+        let rules: Vec<_> = rule_ids
+            .iter()
+            .map(|rule_id| {
+                json!({
+                    "id": rule_id,
+                    "shortDescription": { "text": format!("The `{}` schema keyword", rule_id) }
+                })
+            })
+            .collect();
+        let sarif = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "ys",
+                        "version": version(),
+                        "informationUri": "https://github.com/aisrael/yaml-schema",
+                        "rules": rules
+                    }
+                },
+                "results": results
+            }]
+        });
+        serde_json::to_string_pretty(&sarif).expect("A SARIF log is always serializable")
+    }
+
+    /// Render a JUnit XML test suite with a test case for each file, which fails if the file
+    /// has any errors
+    fn render_junit(&self) -> String {
+        let failures = self
+            .files
+            .iter()
+            .filter(|file| !file.errors.is_empty())
+            .count();
+        let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        xml.push_str(&format!(
+            "<testsuite name=\"ys\" tests=\"{}\" failures=\"{}\" errors=\"0\">\n",
+            self.files.len(),
+            failures
+        ));
+        for file in &self.files {
+            let name = xml_escape(&file.file);
+            if file.errors.is_empty() {
+                xml.push_str(&format!(
+                    "  <testcase name=\"{}\" classname=\"ys\"/>\n",
+                    name
+                ));
+                continue;
+            }
+            let message = match file.errors.len() {
+                1 => "1 validation error".to_string(),
+                n => format!("{} validation errors", n),
+            };
+            let details = file
+                .errors
+                .iter()
+                .map(|error| xml_escape(&error.to_string()))
+                .collect::<Vec<String>>()
+                .join("\n");
+            xml.push_str(&format!(
+                "  <testcase name=\"{}\" classname=\"ys\">\n    <failure message=\"{}\" type=\"ValidationError\">{}</failure>\n  </testcase>\n",
+                name, message, details
+            ));
+        }
+        xml.push_str("</testsuite>");
+        xml
+    }
+
+    /// Render an `::error` workflow command for each error
+    fn render_github(&self) -> String {
+        self.files
+            .iter()
+            .flat_map(|file| {
+                file.errors.iter().map(|error| {
+                    let mut properties = format!("file={}", github_property(&file.file));
+                    if let Some(line_col) = &error.line_col {
+                        properties
+                            .push_str(&format!(",line={},col={}", line_col.line, line_col.col));
+                    }
+                    let message = format!(".{}: {}", error.path, error.error);
+                    format!("::error {}::{}", properties, github_data(&message))
+                })
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// Escape the data of a workflow command, so that it stays on one line
+fn github_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a workflow command property value, which can't contain `:` or `,` either
+fn github_property(s: &str) -> String {
+    github_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// Escape the characters that can't appear as-is in XML text or attribute values
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The id of the rule that an error breaks, which is the schema keyword that failed
+fn rule_id(error: &ValidationError) -> &'static str {
+    error.kind.keyword().unwrap_or("schema")
+}
+
+/// Convert a file path to a URI: a relative URI reference for a relative path, and a `file:`
+/// URI for an absolute one. Pseudo-paths such as `<stdin>` aren't files, so they have no URI.
+fn file_uri(file: &str) -> Option<String> {
+    if file.starts_with('<') && file.ends_with('>') {
+        return None;
+    }
+    let path = file.replace('\\', "/");
+    let (prefix, path) = match path.as_bytes() {
+        // A Windows drive path, e.g. `C:/schemas/app.yaml`
+        [drive, b':', b'/', ..] if drive.is_ascii_alphabetic() => {
+            (format!("file:///{}", &path[..2]), &path[2..])
+        }
+        [b'/', ..] => ("file://".to_string(), &path[..]),
+        _ => (String::new(), &path[..]),
+    };
+    // Everything but the unreserved characters and the path separator is percent-encoded
+    let encoded: String = path
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            b => format!("%{:02X}", b),
+        })
+        .collect();
+    Some(prefix + &encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::RootSchema;
+
+    use super::*;
+
+    fn report() -> Report {
+        let root_schema = RootSchema::load_from_str(
+            r#"
+            type: object
+            properties:
+              foo:
+                type: string
+            "#,
+        )
+        .unwrap();
+        let context = Engine::evaluate(&root_schema, "foo: 42", false).unwrap();
+        let mut report = Report::new();
+        report.add_file("invalid.yaml", context.errors.take());
+        report.add_file("valid.yaml", vec![]);
+        report
+    }
+
+    #[test]
+    fn test_render_text() {
+        assert_eq!(
+            report().render(ReportFormat::Text),
+            "invalid.yaml: [1:6] .foo: Expected a string, but got: Integer(42) (/properties/foo/type)"
+        );
+    }
+
+    #[test]
+    fn test_render_json() {
+        let expected = r#"[
+  {
+    "file": "invalid.yaml",
+    "document": 0,
+    "line": 1,
+    "column": 6,
+    "path": "foo",
+    "message": "Expected a string, but got: Integer(42)"
+  }
+]"#;
+        assert_eq!(report().render(ReportFormat::Json), expected);
+    }
+
+    #[test]
+    fn test_render_sarif() {
+        let sarif: serde_json::Value =
+            serde_json::from_str(&report().render(ReportFormat::Sarif)).unwrap();
+        assert_eq!(sarif["version"], "2.1.0");
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["name"], "ys");
+        assert_eq!(run["tool"]["driver"]["version"], version());
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "type");
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "type");
+        assert_eq!(
+            result["message"]["text"],
+            "Expected a string, but got: Integer(42)"
+        );
+        let physical_location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(physical_location["artifactLocation"]["uri"], "invalid.yaml");
+        assert_eq!(physical_location["region"]["startLine"], 1);
+        assert_eq!(physical_location["region"]["startColumn"], 6);
+    }
+
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(
+            file_uri("k8s/my app#1.yaml").as_deref(),
+            Some("k8s/my%20app%231.yaml")
+        );
+        assert_eq!(file_uri("a:b?.yaml").as_deref(), Some("a%3Ab%3F.yaml"));
+        assert_eq!(
+            file_uri("C:\\schemas\\app.yaml").as_deref(),
+            Some("file:///C:/schemas/app.yaml")
+        );
+        assert_eq!(
+            file_uri("/srv/déploiement.yaml").as_deref(),
+            Some("file:///srv/d%C3%A9ploiement.yaml")
+        );
+        assert_eq!(file_uri("<stdin>"), None);
+    }
+
+    #[test]
+    fn test_render_junit() {
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuite name="ys" tests="2" failures="1" errors="0">
+  <testcase name="invalid.yaml" classname="ys">
+    <failure message="1 validation error" type="ValidationError">[1:6] .foo: Expected a string, but got: Integer(42) (/properties/foo/type)</failure>
+  </testcase>
+  <testcase name="valid.yaml" classname="ys"/>
+</testsuite>"#;
+        assert_eq!(report().render(ReportFormat::Junit), expected);
+        assert_eq!(xml_escape("a < b & 'c'"), "a &lt; b &amp; &apos;c&apos;");
+    }
+
+    #[test]
+    fn test_render_github() {
+        assert_eq!(
+            report().render(ReportFormat::Github),
+            "::error file=invalid.yaml,line=1,col=6::.foo: Expected a string, but got: Integer(42)"
+        );
+        assert_eq!(github_property("C:\\a,b.yaml"), "C%3A\\a%2Cb.yaml");
+        assert_eq!(github_data("100%\nsure"), "100%25%0Asure");
+    }
+
+    #[test]
+    fn test_render_yaml() {
+        let yaml = report().render(ReportFormat::Yaml);
+        let docs = saphyr::Yaml::load_from_str(&yaml).unwrap();
+        let error = &docs[0][0];
+        assert_eq!(error["file"].as_str(), Some("invalid.yaml"));
+        assert_eq!(error["document"].as_i64(), Some(0));
+        assert_eq!(error["line"].as_i64(), Some(1));
+        assert_eq!(error["column"].as_i64(), Some(6));
+        assert_eq!(error["path"].as_str(), Some("foo"));
+        assert_eq!(
+            error["message"].as_str(),
+            Some("Expected a string, but got: Integer(42)")
+        );
+    }
+
+    #[test]
+    fn test_file_error_fails_the_file() {
+        let mut report = Report::new();
+        report.add_file_error("malformed.yaml", "YAML parsing error");
+        assert!(report.has_errors());
+        assert_eq!(
+            report.render(ReportFormat::Text),
+            "malformed.yaml: .: YAML parsing error"
+        );
+        assert!(report
+            .render(ReportFormat::Junit)
+            .contains("tests=\"1\" failures=\"1\""));
+        assert_eq!(
+            report.render(ReportFormat::Github),
+            "::error file=malformed.yaml::.: YAML parsing error"
+        );
+    }
+}
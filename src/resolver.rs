@@ -0,0 +1,180 @@
+/// Resolution of `$ref` URIs that point outside of the referring schema document
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::Error;
+use crate::Result;
+
+/// A SchemaResolver locates and reads the schema documents referenced by a `$ref`,
+/// such as `$ref: ./common/network.yaml#/$defs/port`
+pub trait SchemaResolver {
+    /// Resolve a reference URI (the part of a `$ref` before the `#`) to the location of the
+    /// referenced schema document.
+    ///
+    /// `base` is the location of the referring schema document, if it has one. Two URIs that
+    /// refer to the same document must resolve to the same location, since locations are used
+    /// to make sure each document is only loaded once.
+    fn resolve(&self, base: Option<&str>, uri: &str) -> Result<String>;
+
+    /// Read the contents of the schema document at a location returned by `resolve`
+    fn read(&self, location: &str) -> Result<String>;
+}
+
+/// The default SchemaResolver, which reads schema documents from the filesystem
+///
+/// Relative paths are resolved against the directory of the referring schema file, or against
+/// the `root` directory when the referring schema has no location (e.g. it was loaded from a string)
+#[derive(Debug)]
+pub struct FileSystemResolver {
+    pub root: PathBuf,
+}
+
+impl FileSystemResolver {
+    pub fn new<P: Into<PathBuf>>(root: P) -> FileSystemResolver {
+        FileSystemResolver { root: root.into() }
+    }
+}
+
+impl Default for FileSystemResolver {
+    /// A FileSystemResolver anchored at the current working directory
+    fn default() -> Self {
+        FileSystemResolver::new(".")
+    }
+}
+
+impl SchemaResolver for FileSystemResolver {
+    fn resolve(&self, base: Option<&str>, uri: &str) -> Result<String> {
+        let path = uri.strip_prefix("file://").unwrap_or(uri);
+        if has_uri_scheme(path) {
+            return Err(generic_error!("Unable to resolve schema URI: {}", uri));
+        }
+        let base_dir = match base {
+            Some(base) => Path::new(base)
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            None => self.root.clone(),
+        };
+        let full_path = base_dir.join(path);
+        let canonical_path =
+            fs::canonicalize(&full_path).map_err(|_| Error::FileNotFound(uri.to_string()))?;
+        Ok(canonical_path.to_string_lossy().to_string())
+    }
+
+    fn read(&self, location: &str) -> Result<String> {
+        let fs_metadata = fs::metadata(location)?;
+        if !fs_metadata.is_file() {
+            return Err(Error::FileNotFound(location.to_string()));
+        }
+        Ok(fs::read_to_string(location)?)
+    }
+}
+
+/// A SchemaResolver that resolves the `$id`s of a set of schema files to those files, and
+/// everything else with another SchemaResolver
+///
+/// This lets a schema `$ref` another schema by its `$id`, e.g.
+/// `$ref: https://example.com/schemas/port.yaml`, without the other schema being published there.
+pub struct IdResolver<'a> {
+    /// The locations of the registered schema files, keyed by their `$id`
+    ids: HashMap<String, String>,
+    resolver: &'a dyn SchemaResolver,
+}
+
+impl<'a> IdResolver<'a> {
+    pub fn new(resolver: &'a dyn SchemaResolver) -> IdResolver<'a> {
+        IdResolver {
+            ids: HashMap::new(),
+            resolver,
+        }
+    }
+
+    /// Register a schema file by its `$id`. A schema without an `$id` can still be referenced
+    /// by its path, so it isn't registered.
+    pub fn register_file(&mut self, path: &str) -> Result<()> {
+        let location = self.resolver.resolve(None, path)?;
+        let docs = saphyr::Yaml::load_from_str(&self.resolver.read(&location)?)?;
+        let id = docs
+            .first()
+            .and_then(|doc| doc.as_hash())
+            .and_then(|hash| hash.get(&saphyr::Yaml::String("$id".to_string())))
+            .and_then(|id| id.as_str());
+        if let Some(id) = id {
+            self.ids.insert(id.to_string(), location);
+        }
+        Ok(())
+    }
+}
+
+impl SchemaResolver for IdResolver<'_> {
+    fn resolve(&self, base: Option<&str>, uri: &str) -> Result<String> {
+        match self.ids.get(uri) {
+            Some(location) => Ok(location.clone()),
+            None => self.resolver.resolve(base, uri),
+        }
+    }
+
+    fn read(&self, location: &str) -> Result<String> {
+        self.resolver.read(location)
+    }
+}
+
+/// Returns true if the URI starts with a scheme, e.g. `https:` or `urn:`
+fn has_uri_scheme(uri: &str) -> bool {
+    match uri.split_once(':') {
+        // A single letter is most likely a Windows drive letter
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_has_uri_scheme() {
+        assert!(has_uri_scheme("https://example.com/schema.yaml"));
+        assert!(has_uri_scheme("urn:example:schema"));
+        assert!(!has_uri_scheme("./common/network.yaml"));
+        assert!(!has_uri_scheme("C:\\schemas\\network.yaml"));
+    }
+
+    #[test]
+    fn test_file_system_resolver() {
+        let resolver = FileSystemResolver::default();
+        let location = resolver
+            .resolve(None, "tests/fixtures/schema.yaml")
+            .unwrap();
+        assert!(location.ends_with("schema.yaml"));
+        let sibling = resolver.resolve(Some(&location), "valid.yaml").unwrap();
+        assert!(sibling.ends_with("valid.yaml"));
+        assert!(resolver
+            .resolve(None, "tests/fixtures/missing.yaml")
+            .is_err());
+    }
+
+    #[test]
+    fn test_id_resolver() {
+        let file_system_resolver = FileSystemResolver::default();
+        let mut resolver = IdResolver::new(&file_system_resolver);
+        resolver
+            .register_file("tests/fixtures/multiple/port.yaml")
+            .unwrap();
+        let location = resolver
+            .resolve(None, "https://example.com/schemas/port.yaml")
+            .unwrap();
+        assert!(location.ends_with("port.yaml"));
+        assert!(resolver
+            .resolve(None, "https://example.com/schemas/missing.yaml")
+            .is_err());
+    }
+}
@@ -2,70 +2,125 @@
 use log::debug;
 use std::fmt;
 
+use crate::validation::ValidationErrorKind;
 use crate::Result;
 use crate::Validator;
 
+mod all_of;
+mod annotated;
 mod any_of;
 mod array;
 mod bool_or_typed;
 mod r#const;
 mod r#enum;
+mod if_then_else;
 mod integer;
+mod multiple_types;
 mod not;
 mod number;
 mod object;
 mod one_of;
+mod r#ref;
 mod string;
 
+pub use all_of::AllOfSchema;
+pub use annotated::AnnotatedSchema;
+pub use annotated::Annotations;
 pub use any_of::AnyOfSchema;
 pub use array::ArraySchema;
 pub use bool_or_typed::BoolOrTypedSchema;
+pub use if_then_else::IfThenElseSchema;
 pub use integer::IntegerSchema;
+pub use multiple_types::MultipleTypesSchema;
 pub use not::NotSchema;
 pub use number::NumberSchema;
 pub use object::ObjectSchema;
 pub use one_of::OneOfSchema;
 pub use r#const::ConstSchema;
 pub use r#enum::EnumSchema;
+pub use r#ref::RefSchema;
 pub use string::StringSchema;
 
 /// A TypedSchema is a subset of YamlSchema that has a `type:`
 #[derive(Debug, PartialEq)]
+#[allow(clippy::large_enum_variant)]
 pub enum TypedSchema {
     Null,
-    Array(ArraySchema),     // `type: array`
-    BooleanSchema,          // `type: boolean`
-    Integer(IntegerSchema), // `type: integer`
-    Number(NumberSchema),   // `type: number`
-    Object(ObjectSchema),   // `type: object`
-    String(StringSchema),   // `type: string`
+    Array(ArraySchema),                 // `type: array`
+    BooleanSchema,                      // `type: boolean`
+    Integer(IntegerSchema),             // `type: integer`
+    Number(NumberSchema),               // `type: number`
+    Object(ObjectSchema),               // `type: object`
+    String(StringSchema),               // `type: string`
+    MultipleTypes(MultipleTypesSchema), // e.g. `type: [string, null]`
 }
 
 /// A type value is either a string or an array of strings
 #[derive(Debug, PartialEq)]
 pub enum TypeValue {
-    Single(saphyr::Yaml),
+    Single(saphyr::MarkedYaml),
     Array(Vec<String>),
 }
 
 impl TypedSchema {
-    pub fn for_yaml_value(value: &saphyr::Yaml) -> Result<TypedSchema> {
-        match value {
-            saphyr::Yaml::Null => Ok(TypedSchema::Null),
-            saphyr::Yaml::String(s) => Ok(TypedSchema::for_type_string(s.as_str())?),
-            _ => panic!("Unknown type: {:?}", value),
+    pub fn for_yaml_value(value: &saphyr::MarkedYaml) -> Result<TypedSchema> {
+        match &value.data {
+            saphyr::YamlData::Null => Ok(TypedSchema::Null),
+            saphyr::YamlData::String(s) => TypedSchema::for_type_string(s.as_str())
+                .map_err(|_| schema_error!(value, "Unsupported type '{}'!", s)),
+            data => Err(schema_error!(
+                value,
+                "Expected type: string, but got: {:?}",
+                data
+            )),
         }
     }
 
     pub fn for_type_string(r#type: &str) -> Result<TypedSchema> {
         match r#type {
+            "null" => Ok(TypedSchema::Null),
             "array" => Ok(TypedSchema::Array(ArraySchema::default())),
             "boolean" => Ok(TypedSchema::BooleanSchema),
             "integer" => Ok(TypedSchema::Integer(IntegerSchema::default())),
             "number" => Ok(TypedSchema::Number(NumberSchema::default())),
             "object" => Ok(TypedSchema::Object(ObjectSchema::default())),
             "string" => Ok(TypedSchema::String(StringSchema::default())),
-            _ => panic!("Unknown type: {}", r#type),
+            _ => Err(unsupported_type!(r#type.to_string())),
+        }
+    }
+}
+
+impl TypedSchema {
+    /// The name of the type, as it appears in `type:`
+    pub fn type_name(&self) -> &str {
+        match self {
+            TypedSchema::Null => "null",
+            TypedSchema::Array(_) => "array",
+            TypedSchema::BooleanSchema => "boolean",
+            TypedSchema::Integer(_) => "integer",
+            TypedSchema::Number(_) => "number",
+            TypedSchema::Object(_) => "object",
+            TypedSchema::String(_) => "string",
+            TypedSchema::MultipleTypes(_) => "multiple",
+        }
+    }
+
+    /// Returns true if the value has this schema's type
+    ///
+    /// As in JSON Schema, an integer is also a number, and a number with a zero fractional
+    /// part (e.g. `1.0`) is also an integer
+    pub fn accepts_type_of(&self, data: &saphyr::YamlData<saphyr::MarkedYaml>) -> bool {
+        match self {
+            TypedSchema::Null => data.is_null(),
+            TypedSchema::Array(_) => data.is_array(),
+            TypedSchema::BooleanSchema => data.is_boolean(),
+            TypedSchema::Integer(_) => {
+                data.is_integer() || data.as_f64().is_some_and(|f| f.fract() == 0.0)
+            }
+            TypedSchema::Number(_) => data.is_integer() || data.is_real(),
+            TypedSchema::Object(_) => data.is_hash(),
+            TypedSchema::String(_) => data.is_string(),
+            TypedSchema::MultipleTypes(m) => m.for_value(data).is_some(),
         }
     }
 }
@@ -80,6 +135,7 @@ impl fmt::Display for TypedSchema {
             TypedSchema::Number(n) => write!(f, "{}", n),
             TypedSchema::Object(o) => write!(f, "{}", o),
             TypedSchema::String(s) => write!(f, "{}", s),
+            TypedSchema::MultipleTypes(m) => write!(f, "{}", m),
         }
     }
 }
@@ -93,7 +149,11 @@ impl Validator for TypedSchema {
             TypedSchema::BooleanSchema => Ok(()),
             TypedSchema::Null => {
                 if !value.data.is_null() {
-                    context.add_error(value, format!("Expected null, but got: {:?}", value));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("null", &value.data),
+                        format!("Expected null, but got: {:?}", value),
+                    );
                 }
                 Ok(())
             }
@@ -101,6 +161,7 @@ impl Validator for TypedSchema {
             TypedSchema::Number(n) => n.validate(context, value),
             TypedSchema::Object(o) => o.validate(context, value),
             TypedSchema::String(s) => s.validate(context, value),
+            TypedSchema::MultipleTypes(m) => m.validate(context, value),
         }
     }
 }
@@ -0,0 +1,19 @@
+use crate::format_vec;
+use crate::YamlSchema;
+
+/// The `allOf` schema matches if all of the schemas in the `allOf` array match. The errors of
+/// every failing schema are added to the context.
+#[derive(Debug, Default, PartialEq)]
+pub struct AllOfSchema {
+    pub all_of: Vec<YamlSchema>,
+    /// True if there is no `allOf` keyword in the schema, and the loader combined sibling
+    /// keywords (e.g. `if` alongside `type`) with this schema. The schemas then share the
+    /// location of this schema, instead of being at `/allOf/{index}`
+    pub implicit: bool,
+}
+
+impl std::fmt::Display for AllOfSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "allOf:{}", format_vec(&self.all_of))
+    }
+}
@@ -0,0 +1,42 @@
+/// Annotation keywords, e.g. `title` and `description`, describe a schema without affecting
+/// whether a value is valid. They're kept on the schema, and collected during validation.
+use crate::YamlSchema;
+
+/// The values of the annotation keywords of a schema
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Annotations {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// The `default` value, which may be of any type
+    pub default: Option<saphyr::MarkedYaml>,
+    pub examples: Option<Vec<saphyr::MarkedYaml>>,
+    pub deprecated: bool,
+    pub read_only: bool,
+    pub write_only: bool,
+    /// The `$comment`, which is meant for schema maintainers rather than for users
+    pub comment: Option<String>,
+}
+
+impl Annotations {
+    /// Returns true if none of the annotation keywords are set
+    pub fn is_empty(&self) -> bool {
+        *self == Annotations::default()
+    }
+}
+
+/// A schema together with its annotations. The annotations are recorded in the context, and the
+/// value is then validated against the schema.
+#[derive(Debug, Default, PartialEq)]
+pub struct AnnotatedSchema {
+    pub annotations: Annotations,
+    pub schema: Box<YamlSchema>,
+}
+
+impl std::fmt::Display for AnnotatedSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.annotations.title {
+            Some(title) => write!(f, "{} ({})", self.schema, title),
+            None => write!(f, "{}", self.schema),
+        }
+    }
+}
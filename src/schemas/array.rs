@@ -1,7 +1,10 @@
 use log::debug;
 
 use crate::format_vec;
+use crate::validation::ValidationErrorKind;
+use crate::yaml_data_eq;
 use crate::Context;
+use crate::Error;
 use crate::Result;
 use crate::Validator;
 use crate::YamlSchema;
@@ -14,14 +17,26 @@ pub struct ArraySchema {
     pub items: Option<BoolOrTypedSchema>,
     pub prefix_items: Option<Vec<YamlSchema>>,
     pub contains: Option<Box<YamlSchema>>,
+    pub min_items: Option<usize>,
+    pub max_items: Option<usize>,
+    pub unique_items: Option<bool>,
+    pub min_contains: Option<usize>,
+    pub max_contains: Option<usize>,
 }
 
 impl std::fmt::Display for ArraySchema {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Array{{ items: {:?}, prefix_items: {:?}, contains: {:?}}}",
-            self.items, self.prefix_items, self.contains
+            "Array{{ items: {:?}, prefix_items: {:?}, contains: {:?}, min_items: {:?}, max_items: {:?}, unique_items: {:?}, min_contains: {:?}, max_contains: {:?}}}",
+            self.items,
+            self.prefix_items,
+            self.contains,
+            self.min_items,
+            self.max_items,
+            self.unique_items,
+            self.min_contains,
+            self.max_contains
         )
     }
 }
@@ -33,20 +48,131 @@ impl Validator for ArraySchema {
         debug!("[ArraySchema] Validating value: {:?}", data);
 
         if !data.is_array() {
-            context.add_error(value, format!("Expected an array, but got: {:?}", value));
+            context.add_error(
+                value,
+                ValidationErrorKind::type_mismatch("array", data),
+                format!("Expected an array, but got: {:?}", data),
+            );
             fail_fast!(context);
             return Ok(());
         }
 
         let array = data.as_vec().unwrap();
 
+        // validate minItems and maxItems
+        if let Some(min_items) = self.min_items {
+            if array.len() < min_items {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::MinItems {
+                        limit: min_items,
+                        actual: array.len(),
+                    },
+                    format!("Array has too few items! Minimum is {}!", min_items),
+                );
+                fail_fast!(context);
+            }
+        }
+        if let Some(max_items) = self.max_items {
+            if array.len() > max_items {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::MaxItems {
+                        limit: max_items,
+                        actual: array.len(),
+                    },
+                    format!("Array has too many items! Maximum is {}!", max_items),
+                );
+                fail_fast!(context);
+            }
+        }
+
+        // validate uniqueItems
+        if self.unique_items == Some(true) {
+            for (i, item) in array.iter().enumerate() {
+                if let Some(j) = array[..i]
+                    .iter()
+                    .position(|other| yaml_data_eq(&item.data, &other.data))
+                {
+                    context.add_error(
+                        item,
+                        ValidationErrorKind::UniqueItems {
+                            index: i,
+                            duplicate_of: j,
+                        },
+                        format!(
+                            "Array items are not unique! Item {} is equal to item {}!",
+                            i, j
+                        ),
+                    );
+                    fail_fast!(context);
+                }
+            }
+        }
+
         // validate contains
         if let Some(sub_schema) = &self.contains {
-            if !array.iter().any(|item| {
-                let sub_context = Context::new(true);
-                sub_schema.validate(&sub_context, item).is_ok()
-            }) {
-                context.add_error(value, "Contains validation failed!".to_string());
+            // count all the matching items, since minContains and maxContains need the total
+            let mut matches = 0;
+            for (i, item) in array.iter().enumerate() {
+                let sub_context = context
+                    .append_path(i.to_string())
+                    .append_schema_path(&["contains"])
+                    .get_sub_context();
+                match sub_schema.validate(&sub_context, item) {
+                    Ok(()) | Err(Error::FailFast) => {
+                        if !sub_context.has_errors() {
+                            context.keep_annotations(&sub_context);
+                            matches += 1;
+                        }
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+            debug!("[ArraySchema] {} items match contains", matches);
+            match self.min_contains {
+                None => {
+                    if matches == 0 {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Contains,
+                            "Contains validation failed!".to_string(),
+                        );
+                        fail_fast!(context);
+                    }
+                }
+                Some(min_contains) => {
+                    if matches < min_contains {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::MinContains {
+                                limit: min_contains,
+                                actual: matches,
+                            },
+                            format!(
+                                "Array contains {} matching items, but at least {} are required!",
+                                matches, min_contains
+                            ),
+                        );
+                        fail_fast!(context);
+                    }
+                }
+            }
+            if let Some(max_contains) = self.max_contains {
+                if matches > max_contains {
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::MaxContains {
+                            limit: max_contains,
+                            actual: matches,
+                        },
+                        format!(
+                            "Array contains {} matching items, but at most {} are allowed!",
+                            matches, max_contains
+                        ),
+                    );
+                    fail_fast!(context);
+                }
             }
         }
 
@@ -63,13 +189,19 @@ impl Validator for ArraySchema {
                         "[ArraySchema] Validating prefix item {} with schema: {}",
                         i, prefix_items[i]
                     );
-                    prefix_items[i].validate(context, item)?;
+                    let sub_context = context
+                        .append_path(i.to_string())
+                        .append_schema_path(&["prefixItems", &i.to_string()]);
+                    prefix_items[i].validate(&sub_context, item)?;
                 } else if let Some(items) = &self.items {
                     // if the index is not within the prefix items, validate against the array items schema
                     debug!(
                         "[ArraySchema] Validating array item {} with schema: {}",
                         i, items
                     );
+                    let sub_context = context
+                        .append_path(i.to_string())
+                        .append_schema_path(&["items"]);
                     match items {
                         BoolOrTypedSchema::Boolean(true) => {
                             // `items: true` allows any items
@@ -78,11 +210,18 @@ impl Validator for ArraySchema {
                         BoolOrTypedSchema::Boolean(false) => {
                             context.add_error(
                                 item,
+                                ValidationErrorKind::ItemNotAllowed { index: i },
                                 "Additional array items are not allowed!".to_string(),
                             );
                         }
                         BoolOrTypedSchema::TypedSchema(typed_schema) => {
-                            typed_schema.validate(context, item)?;
+                            typed_schema.validate(&sub_context, item)?;
+                        }
+                        BoolOrTypedSchema::Reference(ref_schema) => {
+                            ref_schema.validate(&sub_context, item)?;
+                        }
+                        BoolOrTypedSchema::Annotated(annotated_schema) => {
+                            annotated_schema.validate(&sub_context, item)?;
                         }
                     }
                 } else {
@@ -98,13 +237,33 @@ impl Validator for ArraySchema {
                         if self.prefix_items.is_none() && !array.is_empty() {
                             context.add_error(
                                 array.first().unwrap(),
+                                ValidationErrorKind::ItemNotAllowed { index: 0 },
                                 "Array items are not allowed!".to_string(),
                             );
                         }
                     }
                     BoolOrTypedSchema::TypedSchema(typed_schema) => {
-                        for item in array {
-                            typed_schema.validate(context, item)?;
+                        for (i, item) in array.iter().enumerate() {
+                            let sub_context = context
+                                .append_path(i.to_string())
+                                .append_schema_path(&["items"]);
+                            typed_schema.validate(&sub_context, item)?;
+                        }
+                    }
+                    BoolOrTypedSchema::Reference(ref_schema) => {
+                        for (i, item) in array.iter().enumerate() {
+                            let sub_context = context
+                                .append_path(i.to_string())
+                                .append_schema_path(&["items"]);
+                            ref_schema.validate(&sub_context, item)?;
+                        }
+                    }
+                    BoolOrTypedSchema::Annotated(annotated_schema) => {
+                        for (i, item) in array.iter().enumerate() {
+                            let sub_context = context
+                                .append_path(i.to_string())
+                                .append_schema_path(&["items"]);
+                            annotated_schema.validate(&sub_context, item)?;
                         }
                     }
                 }
@@ -140,8 +299,8 @@ mod tests {
         let value = docs.first().unwrap();
         let context = crate::Context::default();
         let result = schema.validate(&context, value);
-        if result.is_err() {
-            println!("{}", result.unwrap_err());
+        if let Err(e) = result {
+            println!("{}", e);
         }
     }
 
@@ -172,16 +331,16 @@ mod tests {
         - Washington
         "#;
 
-        let s_docs = saphyr::Yaml::load_from_str(schema_string).unwrap();
+        let s_docs = saphyr::MarkedYaml::load_from_str(schema_string).unwrap();
         let first_schema = s_docs.first().unwrap();
-        let array_schema_hash = first_schema.as_hash().unwrap();
+        let array_schema_hash = first_schema.data.as_hash().unwrap();
         let schema = ArraySchema::construct(array_schema_hash).unwrap();
         let docs = saphyr::MarkedYaml::load_from_str(yaml_string).unwrap();
         let value = docs.first().unwrap();
         let context = crate::Context::default();
         let result = schema.validate(&context, value);
-        if result.is_err() {
-            println!("{}", result.unwrap_err());
+        if let Err(e) = result {
+            println!("{}", e);
         }
     }
 
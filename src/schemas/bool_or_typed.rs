@@ -1,9 +1,15 @@
+use crate::AnnotatedSchema;
+use crate::RefSchema;
 use crate::TypedSchema;
 
 #[derive(Debug, PartialEq)]
 pub enum BoolOrTypedSchema {
     Boolean(bool),
     TypedSchema(Box<TypedSchema>),
+    Reference(RefSchema),
+    /// A typed schema or reference, with annotations, e.g. `description`, or a reference with
+    /// other keywords alongside it
+    Annotated(AnnotatedSchema),
 }
 
 impl std::fmt::Display for BoolOrTypedSchema {
@@ -11,6 +17,8 @@ impl std::fmt::Display for BoolOrTypedSchema {
         match self {
             BoolOrTypedSchema::Boolean(b) => write!(f, "{}", b),
             BoolOrTypedSchema::TypedSchema(s) => write!(f, "{}", s),
+            BoolOrTypedSchema::Reference(r) => write!(f, "{}", r),
+            BoolOrTypedSchema::Annotated(a) => write!(f, "{}", a),
         }
     }
 }
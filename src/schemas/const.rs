@@ -1,6 +1,7 @@
 use crate::Number;
 use log::debug;
 
+use crate::validation::ValidationErrorKind;
 use crate::ConstValue;
 use crate::Context;
 use crate::Result;
@@ -19,6 +20,14 @@ impl std::fmt::Display for ConstSchema {
     }
 }
 
+impl ConstSchema {
+    fn mismatch(&self) -> ValidationErrorKind {
+        ValidationErrorKind::ConstMismatch {
+            expected: self.r#const.clone(),
+        }
+    }
+}
+
 impl Validator for ConstSchema {
     fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
         let data = &value.data;
@@ -34,13 +43,13 @@ impl Validator for ConstSchema {
                         "Const validation failed, expected: {:?}, got: {:?}",
                         b, data
                     );
-                    context.add_error(value, error);
+                    context.add_error(value, self.mismatch(), error);
                 }
             }
             ConstValue::Null => {
                 if !data.is_null() {
                     let error = format!("Const validation failed, expected: null, got: {:?}", data);
-                    context.add_error(value, error);
+                    context.add_error(value, self.mismatch(), error);
                 }
             }
             ConstValue::Number(n) => match n {
@@ -51,14 +60,14 @@ impl Validator for ConstSchema {
                                 "Const validation failed, expected: {:?}, got: {:?}",
                                 i, data
                             );
-                            context.add_error(value, error);
+                            context.add_error(value, self.mismatch(), error);
                         }
                     } else {
                         let error = format!(
                             "Const validation failed, expected: {:?}, got: {:?}",
                             i, data
                         );
-                        context.add_error(value, error);
+                        context.add_error(value, self.mismatch(), error);
                     }
                 }
                 Number::Float(f) => {
@@ -68,14 +77,14 @@ impl Validator for ConstSchema {
                                 "Const validation failed, expected: {:?}, got: {:?}",
                                 f, data
                             );
-                            context.add_error(value, error);
+                            context.add_error(value, self.mismatch(), error);
                         }
                     } else {
                         let error = format!(
                             "Const validation failed, expected: {:?}, got: {:?}",
                             f, data
                         );
-                        context.add_error(value, error);
+                        context.add_error(value, self.mismatch(), error);
                     }
                 }
             },
@@ -85,7 +94,7 @@ impl Validator for ConstSchema {
                         "Const validation failed, expected: {:?}, got: {:?}",
                         s, data
                     );
-                    context.add_error(value, error);
+                    context.add_error(value, self.mismatch(), error);
                 }
             }
         }
@@ -2,9 +2,9 @@ use log::debug;
 
 use crate::format_vec;
 use crate::format_yaml_data;
+use crate::validation::ValidationErrorKind;
 use crate::ConstValue;
 use crate::Context;
-use crate::Error;
 use crate::Result;
 use crate::Validator;
 
@@ -25,17 +25,15 @@ impl Validator for EnumSchema {
         debug!("[EnumSchema] self: {}", self);
         let data = &value.data;
         debug!("[EnumSchema] Validating value: {:?}", data);
-        let const_value: ConstValue = data.try_into().map_err(|_| {
-            Error::GenericError(format!("Unable to convert value: {:?} to ConstValue", data))
-        })?;
-        debug!("[EnumSchema] const_value: {}", const_value);
-        for value in &self.r#enum {
-            debug!("[EnumSchema] value: {}", value);
-            if value.eq(&const_value) {
-                return Ok(());
+        // Only scalars can be in an enum, so a collection never matches
+        let is_allowed = match ConstValue::try_from(data) {
+            Ok(const_value) => {
+                debug!("[EnumSchema] const_value: {}", const_value);
+                self.r#enum.contains(&const_value)
             }
-        }
-        if !self.r#enum.contains(&const_value) {
+            Err(_) => false,
+        };
+        if !is_allowed {
             let value_str = format_yaml_data(data);
             let enum_values = self
                 .r#enum
@@ -45,7 +43,13 @@ impl Validator for EnumSchema {
                 .join(", ");
             let error = format!("Value {} is not in the enum: [{}]", value_str, enum_values);
             debug!("[EnumSchema] error: {}", error);
-            context.add_error(value, error);
+            context.add_error(
+                value,
+                ValidationErrorKind::EnumMismatch {
+                    allowed: self.r#enum.clone(),
+                },
+                error,
+            );
         }
         Ok(())
     }
@@ -0,0 +1,23 @@
+use crate::YamlSchema;
+
+/// The `if`, `then` and `else` keywords apply a subschema conditionally: `then` if the value
+/// is valid against `if`, and `else` otherwise.
+#[derive(Debug, Default, PartialEq)]
+pub struct IfThenElseSchema {
+    pub r#if: Box<YamlSchema>,
+    pub then: Option<Box<YamlSchema>>,
+    pub r#else: Option<Box<YamlSchema>>,
+}
+
+impl std::fmt::Display for IfThenElseSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "if: {}", self.r#if)?;
+        if let Some(then) = &self.then {
+            write!(f, ", then: {}", then)?;
+        }
+        if let Some(r#else) = &self.r#else {
+            write!(f, ", else: {}", r#else)?;
+        }
+        Ok(())
+    }
+}
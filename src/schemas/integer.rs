@@ -1,6 +1,7 @@
 use log::debug;
 
 use crate::validation::Context;
+use crate::validation::ValidationErrorKind;
 use crate::validation::Validator;
 use crate::Number;
 use crate::Result;
@@ -30,7 +31,11 @@ impl Validator for IntegerSchema {
             match data.as_i64() {
                 Some(i) => self.validate_number_i64(context, value, i),
                 None => {
-                    context.add_error(value, format!("Expected an integer, but got: {:?}", data));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("integer", data),
+                        format!("Expected an integer, but got: {:?}", data),
+                    );
                 }
             }
         } else if data.is_real() {
@@ -39,16 +44,27 @@ impl Validator for IntegerSchema {
                     if f.fract() == 0.0 {
                         self.validate_number_i64(context, value, f as i64);
                     } else {
-                        context
-                            .add_error(value, format!("Expected an integer, but got: {:?}", data));
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::type_mismatch("integer", data),
+                            format!("Expected an integer, but got: {:?}", data),
+                        );
                     }
                 }
                 None => {
-                    context.add_error(value, format!("Expected a float, but got: {:?}", data));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("number", data),
+                        format!("Expected a float, but got: {:?}", data),
+                    );
                 }
             }
         } else {
-            context.add_error(value, format!("Expected a number, but got: {:?}", data));
+            context.add_error(
+                value,
+                ValidationErrorKind::type_mismatch("integer", data),
+                format!("Expected a number, but got: {:?}", data),
+            );
         }
         if !context.errors.borrow().is_empty() {
             fail_fast!(context)
@@ -63,12 +79,26 @@ impl IntegerSchema {
             match minimum {
                 Number::Integer(min) => {
                     if i < *min {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
                 Number::Float(min) => {
                     if (i as f64) < *min {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
             }
@@ -77,28 +107,89 @@ impl IntegerSchema {
             match maximum {
                 Number::Integer(max) => {
                     if i > *max {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
                 Number::Float(max) => {
                     if (i as f64) > *max {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
             }
         }
+        if let Some(exclusive_minimum) = &self.exclusive_minimum {
+            let is_too_small = match exclusive_minimum {
+                Number::Integer(min) => i <= *min,
+                Number::Float(min) => (i as f64) <= *min,
+            };
+            if is_too_small {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMinimum {
+                        limit: *exclusive_minimum,
+                        actual: Number::Integer(i),
+                    },
+                    "Number is too small!".to_string(),
+                );
+            }
+        }
+        if let Some(exclusive_maximum) = &self.exclusive_maximum {
+            let is_too_big = match exclusive_maximum {
+                Number::Integer(max) => i >= *max,
+                Number::Float(max) => (i as f64) >= *max,
+            };
+            if is_too_big {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMaximum {
+                        limit: *exclusive_maximum,
+                        actual: Number::Integer(i),
+                    },
+                    "Number is too big!".to_string(),
+                );
+            }
+        }
         if let Some(multiple_of) = &self.multiple_of {
             match multiple_of {
                 Number::Integer(multiple) => {
-                    if i % *multiple != 0 {
-                        context
-                            .add_error(value, format!("Number is not a multiple of {}!", multiple));
+                    // The loader rejects a `multipleOf` of 0, but the remainder is still checked
+                    if i.checked_rem(*multiple)
+                        .is_some_and(|remainder| remainder != 0)
+                    {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::MultipleOf {
+                                multiple_of: *multiple_of,
+                                actual: Number::Integer(i),
+                            },
+                            format!("Number is not a multiple of {}!", multiple),
+                        );
                     }
                 }
                 Number::Float(multiple) => {
-                    if (i as f64) % *multiple != 0.0 {
-                        context
-                            .add_error(value, format!("Number is not a multiple of {}!", multiple));
+                    if *multiple != 0.0 && (i as f64) % *multiple != 0.0 {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::MultipleOf {
+                                multiple_of: *multiple_of,
+                                actual: Number::Integer(i),
+                            },
+                            format!("Number is not a multiple of {}!", multiple),
+                        );
                     }
                 }
             }
@@ -124,5 +215,76 @@ mod tests {
             first_error.error,
             "Expected a number, but got: String(\"foo\")"
         );
+        assert_eq!(
+            first_error.kind,
+            ValidationErrorKind::TypeMismatch {
+                expected: "integer".to_string(),
+                found: "string".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_integer_schema_minimum() {
+        let schema = IntegerSchema {
+            minimum: Some(Number::integer(10)),
+            ..Default::default()
+        };
+        let context = Context::default();
+        let docs = saphyr::MarkedYaml::load_from_str("5").unwrap();
+        schema.validate(&context, docs.first().unwrap()).unwrap();
+        let errors = context.errors.borrow();
+        let first_error = errors.first().unwrap();
+        assert_eq!(first_error.error, "Number is too small!");
+        assert_eq!(
+            first_error.kind,
+            ValidationErrorKind::Minimum {
+                limit: Number::Integer(10),
+                actual: Number::Integer(5)
+            }
+        );
+    }
+
+    #[test]
+    fn test_integer_schema_multiple_of_zero() {
+        // The loader rejects it, but a schema constructed in code mustn't panic
+        let schema = IntegerSchema {
+            multiple_of: Some(Number::integer(0)),
+            ..Default::default()
+        };
+        let context = Context::default();
+        let docs = saphyr::MarkedYaml::load_from_str("42").unwrap();
+        schema.validate(&context, docs.first().unwrap()).unwrap();
+        assert!(!context.has_errors());
+    }
+
+    #[test]
+    fn test_integer_schema_exclusive_bounds() {
+        let schema = IntegerSchema {
+            exclusive_minimum: Some(Number::integer(0)),
+            exclusive_maximum: Some(Number::float(10.5)),
+            ..Default::default()
+        };
+        let context = Context::default();
+        let docs = saphyr::MarkedYaml::load_from_str("[0, 1, 10, 11]").unwrap();
+        for value in docs.first().unwrap().data.as_vec().unwrap() {
+            schema.validate(&context, value).unwrap();
+        }
+        let errors = context.errors.borrow();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].kind,
+            ValidationErrorKind::ExclusiveMinimum {
+                limit: Number::Integer(0),
+                actual: Number::Integer(0)
+            }
+        );
+        assert_eq!(
+            errors[1].kind,
+            ValidationErrorKind::ExclusiveMaximum {
+                limit: Number::Float(10.5),
+                actual: Number::Integer(11)
+            }
+        );
     }
 }
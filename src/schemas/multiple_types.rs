@@ -0,0 +1,99 @@
+use log::debug;
+
+use crate::format_vec;
+use crate::format_yaml_data;
+use crate::validation::ValidationErrorKind;
+use crate::Context;
+use crate::Result;
+use crate::Validator;
+
+use super::TypedSchema;
+
+/// A schema with more than one type, e.g. `type: [string, "null"]`
+///
+/// The value is validated against the TypedSchema for the value's actual type, so the
+/// type-specific keywords (`minLength`, `minimum`, `properties`, ...) only apply to values
+/// of that type
+#[derive(Debug, Default, PartialEq)]
+pub struct MultipleTypesSchema {
+    pub types: Vec<TypedSchema>,
+}
+
+impl MultipleTypesSchema {
+    /// Returns the TypedSchema that applies to the value, if the value has any of the types
+    pub fn for_value(&self, data: &saphyr::YamlData<saphyr::MarkedYaml>) -> Option<&TypedSchema> {
+        self.types
+            .iter()
+            .find(|typed_schema| typed_schema.accepts_type_of(data))
+    }
+
+    /// The names of the types, e.g. `["string", "null"]`
+    pub fn type_names(&self) -> Vec<&str> {
+        self.types.iter().map(TypedSchema::type_name).collect()
+    }
+}
+
+impl std::fmt::Display for MultipleTypesSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "type: {}", format_vec(&self.types))
+    }
+}
+
+impl Validator for MultipleTypesSchema {
+    fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
+        debug!("[MultipleTypesSchema] self: {}", self);
+        let data = &value.data;
+        debug!("[MultipleTypesSchema] Validating value: {:?}", data);
+        match self.for_value(data) {
+            Some(typed_schema) => typed_schema.validate(context, value),
+            None => {
+                let expected = format_vec(&self.type_names());
+                context.add_error(
+                    value,
+                    ValidationErrorKind::type_mismatch(&expected, data),
+                    format!(
+                        "Expected one of {}, but got: {}",
+                        expected,
+                        format_yaml_data(data)
+                    ),
+                );
+                fail_fast!(context);
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IntegerSchema;
+    use crate::StringSchema;
+
+    use super::*;
+
+    #[test]
+    fn test_multiple_types_schema() {
+        let schema = MultipleTypesSchema {
+            types: vec![
+                TypedSchema::String(StringSchema {
+                    min_length: Some(3),
+                    ..Default::default()
+                }),
+                TypedSchema::Integer(IntegerSchema::default()),
+            ],
+        };
+        let docs = saphyr::MarkedYaml::load_from_str("[foo, 42, fo, true]").unwrap();
+        let array = docs.first().unwrap().data.as_vec().unwrap();
+        let context = Context::default();
+        for value in array {
+            schema.validate(&context, value).unwrap();
+        }
+        let errors = context.errors.borrow();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].error, "String is too short! (min length: 3)");
+        assert_eq!(
+            errors[1].error,
+            "Expected one of [string, integer], but got: true"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::validation::Context;
+use crate::validation::ValidationErrorKind;
 use crate::validation::Validator;
 use crate::Number;
 use crate::Result;
@@ -26,18 +27,30 @@ impl Validator for NumberSchema {
             match data.as_i64() {
                 Some(i) => self.validate_number_i64(context, value, i),
                 None => {
-                    context.add_error(value, format!("Expected an integer, but got: {:?}", data));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("integer", data),
+                        format!("Expected an integer, but got: {:?}", data),
+                    );
                 }
             }
         } else if data.is_real() {
             match data.as_f64() {
                 Some(f) => self.validate_number_f64(context, value, f),
                 None => {
-                    context.add_error(value, format!("Expected a float, but got: {:?}", data));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("number", data),
+                        format!("Expected a float, but got: {:?}", data),
+                    );
                 }
             }
         } else {
-            context.add_error(value, format!("Expected a number, but got: {:?}", data));
+            context.add_error(
+                value,
+                ValidationErrorKind::type_mismatch("number", data),
+                format!("Expected a number, but got: {:?}", data),
+            );
         }
         if !context.errors.borrow().is_empty() {
             fail_fast!(context)
@@ -52,12 +65,26 @@ impl NumberSchema {
             match minimum {
                 Number::Integer(min) => {
                     if i < *min {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
                 Number::Float(min) => {
                     if (i as f64) < *min {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
             }
@@ -66,28 +93,89 @@ impl NumberSchema {
             match maximum {
                 Number::Integer(max) => {
                     if i > *max {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
                 Number::Float(max) => {
                     if (i as f64) > *max {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Integer(i),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
             }
         }
+        if let Some(exclusive_minimum) = &self.exclusive_minimum {
+            let is_too_small = match exclusive_minimum {
+                Number::Integer(min) => i <= *min,
+                Number::Float(min) => (i as f64) <= *min,
+            };
+            if is_too_small {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMinimum {
+                        limit: *exclusive_minimum,
+                        actual: Number::Integer(i),
+                    },
+                    "Number is too small!".to_string(),
+                );
+            }
+        }
+        if let Some(exclusive_maximum) = &self.exclusive_maximum {
+            let is_too_big = match exclusive_maximum {
+                Number::Integer(max) => i >= *max,
+                Number::Float(max) => (i as f64) >= *max,
+            };
+            if is_too_big {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMaximum {
+                        limit: *exclusive_maximum,
+                        actual: Number::Integer(i),
+                    },
+                    "Number is too big!".to_string(),
+                );
+            }
+        }
         if let Some(multiple_of) = &self.multiple_of {
             match multiple_of {
                 Number::Integer(multiple) => {
-                    if i % *multiple != 0 {
-                        context
-                            .add_error(value, format!("Number is not a multiple of {}!", multiple));
+                    // The loader rejects a `multipleOf` of 0, but the remainder is still checked
+                    if i.checked_rem(*multiple)
+                        .is_some_and(|remainder| remainder != 0)
+                    {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::MultipleOf {
+                                multiple_of: *multiple_of,
+                                actual: Number::Integer(i),
+                            },
+                            format!("Number is not a multiple of {}!", multiple),
+                        );
                     }
                 }
                 Number::Float(multiple) => {
-                    if (i as f64) % *multiple != 0.0 {
-                        context
-                            .add_error(value, format!("Number is not a multiple of {}!", multiple));
+                    if *multiple != 0.0 && (i as f64) % *multiple != 0.0 {
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::MultipleOf {
+                                multiple_of: *multiple_of,
+                                actual: Number::Integer(i),
+                            },
+                            format!("Number is not a multiple of {}!", multiple),
+                        );
                     }
                 }
             }
@@ -98,12 +186,26 @@ impl NumberSchema {
             match minimum {
                 Number::Integer(min) => {
                     if f < *min as f64 {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Float(f),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
                 Number::Float(min) => {
                     if f < *min {
-                        context.add_error(value, "Number is too small!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Minimum {
+                                limit: *minimum,
+                                actual: Number::Float(f),
+                            },
+                            "Number is too small!".to_string(),
+                        );
                     }
                 }
             }
@@ -112,15 +214,61 @@ impl NumberSchema {
             match maximum {
                 Number::Integer(max) => {
                     if f > *max as f64 {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Float(f),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
                 Number::Float(max) => {
                     if f > *max {
-                        context.add_error(value, "Number is too big!".to_string());
+                        context.add_error(
+                            value,
+                            ValidationErrorKind::Maximum {
+                                limit: *maximum,
+                                actual: Number::Float(f),
+                            },
+                            "Number is too big!".to_string(),
+                        );
                     }
                 }
             }
         }
+        if let Some(exclusive_minimum) = &self.exclusive_minimum {
+            let is_too_small = match exclusive_minimum {
+                Number::Integer(min) => f <= *min as f64,
+                Number::Float(min) => f <= *min,
+            };
+            if is_too_small {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMinimum {
+                        limit: *exclusive_minimum,
+                        actual: Number::Float(f),
+                    },
+                    "Number is too small!".to_string(),
+                );
+            }
+        }
+        if let Some(exclusive_maximum) = &self.exclusive_maximum {
+            let is_too_big = match exclusive_maximum {
+                Number::Integer(max) => f >= *max as f64,
+                Number::Float(max) => f >= *max,
+            };
+            if is_too_big {
+                context.add_error(
+                    value,
+                    ValidationErrorKind::ExclusiveMaximum {
+                        limit: *exclusive_maximum,
+                        actual: Number::Float(f),
+                    },
+                    "Number is too big!".to_string(),
+                );
+            }
+        }
     }
 }
@@ -13,6 +13,10 @@ pub struct ObjectSchema {
     pub property_names: Option<String>,
     pub min_properties: Option<usize>,
     pub max_properties: Option<usize>,
+    /// The properties that are required when the key property is present
+    pub dependent_required: Option<HashMap<String, Vec<String>>>,
+    /// The schemas the whole object must match when the key property is present
+    pub dependent_schemas: Option<HashMap<String, YamlSchema>>,
 }
 
 impl std::fmt::Display for ObjectSchema {
@@ -0,0 +1,20 @@
+/// The `$ref` keyword references another schema, usually one declared under `$defs`
+/// in the same document. References are resolved against the root schema at validation time.
+#[derive(Debug, Default, PartialEq)]
+pub struct RefSchema {
+    pub r#ref: String,
+}
+
+impl RefSchema {
+    pub fn new<V: Into<String>>(reference: V) -> RefSchema {
+        RefSchema {
+            r#ref: reference.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for RefSchema {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "$ref: {}", self.r#ref)
+    }
+}
@@ -6,6 +6,7 @@ pub struct StringSchema {
     pub min_length: Option<usize>,
     pub max_length: Option<usize>,
     pub pattern: Option<Regex>,
+    pub format: Option<String>,
 }
 
 impl PartialEq for StringSchema {
@@ -13,6 +14,7 @@ impl PartialEq for StringSchema {
         self.min_length == other.min_length
             && self.max_length == other.max_length
             && are_patterns_equal(&self.pattern, &other.pattern)
+            && self.format == other.format
     }
 }
 
@@ -31,8 +33,8 @@ impl std::fmt::Display for StringSchema {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "StringSchema {{ min_length: {:?}, max_length: {:?}, pattern: {:?} }}",
-            self.min_length, self.max_length, self.pattern
+            "StringSchema {{ min_length: {:?}, max_length: {:?}, pattern: {:?}, format: {:?} }}",
+            self.min_length, self.max_length, self.pattern, self.format
         )
     }
 }
@@ -1,11 +1,19 @@
+mod all_of;
+mod annotated;
 pub mod any_of;
 /// Validation engine for YamlSchema
 mod context;
+pub mod formats;
+mod if_then_else;
 mod not;
 mod objects;
 mod one_of;
+mod r#ref;
 mod strings;
 
+use crate::Annotations;
+use crate::ConstValue;
+use crate::Number;
 use crate::Result;
 use crate::YamlSchema;
 pub use context::Context;
@@ -16,7 +24,7 @@ pub trait Validator {
     fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()>;
 }
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub struct LineCol {
     pub line: usize,
     pub col: usize,
@@ -31,18 +39,135 @@ impl From<&saphyr::MarkedYaml> for LineCol {
     }
 }
 
+impl LineCol {
+    /// The line and column just past the end of the value. Collections have an empty span, so
+    /// their end is the same as their start.
+    pub fn end_of(value: &saphyr::MarkedYaml) -> LineCol {
+        LineCol {
+            line: value.span.end.line(),
+            col: value.span.end.col() + 1,
+        }
+    }
+}
+
 /// A validation error simply contains a path and an error message
 #[derive(Debug)]
 pub struct ValidationError {
     /// The path to the value that caused the error
     pub path: String,
+    /// The path to the value that caused the error, as a JSON Pointer, e.g. `/labels/app`
+    pub instance_location: String,
+    /// The location of the schema keyword that caused the error, as a JSON Pointer from the
+    /// root schema, e.g. `/properties/port/maximum`. Like JSON Schema's `keywordLocation`, it
+    /// includes any `$ref` that was followed to reach the keyword.
+    pub keyword_location: String,
+    /// The index of the document that caused the error, in a multi-document YAML stream
+    pub document_index: usize,
     /// The line and column of the value that caused the error
     pub line_col: Option<LineCol>,
+    /// The line and column just past the end of the value that caused the error
+    pub end_line_col: Option<LineCol>,
+    /// The kind of error, with the details needed to handle it without parsing the message
+    pub kind: ValidationErrorKind,
     /// The error message
     pub error: String,
 }
 
-/// Display this ValidationErrors as "{path}: {error}"
+/// The annotations of a schema that a value was evaluated against, e.g. its `title` and
+/// `description`
+#[derive(Debug)]
+pub struct Annotation {
+    /// The path to the annotated value, as a JSON Pointer, e.g. `/labels/app`
+    pub instance_location: String,
+    /// The location of the annotated schema, as a JSON Pointer from the root schema
+    pub keyword_location: String,
+    /// The index of the annotated document, in a multi-document YAML stream
+    pub document_index: usize,
+    pub annotations: Annotations,
+}
+
+/// The kinds of validation error, one for each way a value can fail a schema keyword
+#[derive(Clone, Debug, PartialEq)]
+pub enum ValidationErrorKind {
+    /// The value isn't of the expected type(s), e.g. `integer` or `[string, null]`
+    TypeMismatch { expected: String, found: String },
+    /// The schema is `false`, so no value is valid
+    FalseSchema,
+    /// The YAML document is empty
+    EmptyDocument,
+    /// The file couldn't be read, or isn't valid YAML, so it couldn't be validated
+    InvalidFile,
+    /// The number is less than `minimum`
+    Minimum { limit: Number, actual: Number },
+    /// The number is greater than `maximum`
+    Maximum { limit: Number, actual: Number },
+    /// The number is less than or equal to `exclusiveMinimum`
+    ExclusiveMinimum { limit: Number, actual: Number },
+    /// The number is greater than or equal to `exclusiveMaximum`
+    ExclusiveMaximum { limit: Number, actual: Number },
+    /// The number is not a multiple of `multipleOf`
+    MultipleOf { multiple_of: Number, actual: Number },
+    /// The string is shorter than `minLength`
+    MinLength { limit: usize, actual: usize },
+    /// The string is longer than `maxLength`
+    MaxLength { limit: usize, actual: usize },
+    /// The string doesn't match the `pattern`
+    PatternMismatch { pattern: String },
+    /// The string is not valid for its `format`
+    FormatMismatch { format: String },
+    /// The `format` is neither built-in nor registered
+    UnknownFormat { format: String },
+    /// A property listed in `required` is missing
+    RequiredMissing { property: String },
+    /// A property listed in `dependentRequired` is missing, while the property it depends on
+    /// is present
+    DependentRequiredMissing {
+        property: String,
+        dependent_on: String,
+    },
+    /// A property is not allowed by `additionalProperties`
+    AdditionalPropertyNotAllowed { property: String },
+    /// A property name doesn't match the `pattern` of `propertyNames`
+    PropertyNameMismatch { property: String, pattern: String },
+    /// The object has fewer than `minProperties`
+    MinProperties { limit: usize, actual: usize },
+    /// The object has more than `maxProperties`
+    MaxProperties { limit: usize, actual: usize },
+    /// The array has fewer than `minItems`
+    MinItems { limit: usize, actual: usize },
+    /// The array has more than `maxItems`
+    MaxItems { limit: usize, actual: usize },
+    /// The array items are not unique, as required by `uniqueItems`
+    UniqueItems { index: usize, duplicate_of: usize },
+    /// No array item matches `contains`
+    Contains,
+    /// Fewer array items than `minContains` match `contains`
+    MinContains { limit: usize, actual: usize },
+    /// More array items than `maxContains` match `contains`
+    MaxContains { limit: usize, actual: usize },
+    /// The array item at this index is not allowed by `items`
+    ItemNotAllowed { index: usize },
+    /// The value is not one of the values in the `enum`
+    EnumMismatch { allowed: Vec<ConstValue> },
+    /// The value is not equal to the `const`
+    ConstMismatch { expected: ConstValue },
+    /// The value matches the schema in `not`
+    NotMatched,
+    /// The value doesn't match any of the schemas in `anyOf`
+    AnyOfNoMatch,
+    /// The value doesn't match any of the schemas in `oneOf`
+    OneOfNoMatch,
+    /// The value matches more than one of the schemas in `oneOf`
+    OneOfMultipleMatches,
+    /// A schema's `$schema` names a draft that schemas can't be checked against
+    UnknownMetaSchema { meta_schema: String },
+    /// A schema uses a keyword that isn't part of the draft named in its `$schema`
+    KeywordNotInDraft { keyword: String, draft: String },
+    /// The value is at a location the schema marks as `deprecated`. Reported as a warning.
+    Deprecated,
+}
+
+/// Display this ValidationErrors as "[{line}:{col}] .{path}: {error} ({keyword_location})"
 impl std::fmt::Display for ValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(line_col) = &self.line_col {
@@ -50,10 +175,87 @@ impl std::fmt::Display for ValidationError {
                 f,
                 "[{}:{}] .{}: {}",
                 line_col.line, line_col.col, self.path, self.error
-            )
+            )?;
         } else {
-            write!(f, ".{}: {}", self.path, self.error)
+            write!(f, ".{}: {}", self.path, self.error)?;
+        }
+        if !self.keyword_location.is_empty() {
+            write!(f, " ({})", self.keyword_location)?;
         }
+        Ok(())
+    }
+}
+
+impl ValidationErrorKind {
+    /// Returns the schema keyword that reports this kind of error, if any
+    pub fn keyword(&self) -> Option<&'static str> {
+        match self {
+            ValidationErrorKind::TypeMismatch { .. } => Some("type"),
+            ValidationErrorKind::FalseSchema
+            | ValidationErrorKind::EmptyDocument
+            | ValidationErrorKind::InvalidFile => None,
+            ValidationErrorKind::Minimum { .. } => Some("minimum"),
+            ValidationErrorKind::Maximum { .. } => Some("maximum"),
+            ValidationErrorKind::ExclusiveMinimum { .. } => Some("exclusiveMinimum"),
+            ValidationErrorKind::ExclusiveMaximum { .. } => Some("exclusiveMaximum"),
+            ValidationErrorKind::MultipleOf { .. } => Some("multipleOf"),
+            ValidationErrorKind::MinLength { .. } => Some("minLength"),
+            ValidationErrorKind::MaxLength { .. } => Some("maxLength"),
+            ValidationErrorKind::PatternMismatch { .. } => Some("pattern"),
+            ValidationErrorKind::FormatMismatch { .. }
+            | ValidationErrorKind::UnknownFormat { .. } => Some("format"),
+            ValidationErrorKind::RequiredMissing { .. } => Some("required"),
+            ValidationErrorKind::DependentRequiredMissing { .. } => Some("dependentRequired"),
+            ValidationErrorKind::AdditionalPropertyNotAllowed { .. } => {
+                Some("additionalProperties")
+            }
+            ValidationErrorKind::PropertyNameMismatch { .. } => Some("propertyNames"),
+            ValidationErrorKind::MinProperties { .. } => Some("minProperties"),
+            ValidationErrorKind::MaxProperties { .. } => Some("maxProperties"),
+            ValidationErrorKind::MinItems { .. } => Some("minItems"),
+            ValidationErrorKind::MaxItems { .. } => Some("maxItems"),
+            ValidationErrorKind::UniqueItems { .. } => Some("uniqueItems"),
+            ValidationErrorKind::Contains => Some("contains"),
+            ValidationErrorKind::MinContains { .. } => Some("minContains"),
+            ValidationErrorKind::MaxContains { .. } => Some("maxContains"),
+            ValidationErrorKind::ItemNotAllowed { .. } => Some("items"),
+            ValidationErrorKind::EnumMismatch { .. } => Some("enum"),
+            ValidationErrorKind::ConstMismatch { .. } => Some("const"),
+            ValidationErrorKind::NotMatched => Some("not"),
+            ValidationErrorKind::AnyOfNoMatch => Some("anyOf"),
+            ValidationErrorKind::OneOfNoMatch | ValidationErrorKind::OneOfMultipleMatches => {
+                Some("oneOf")
+            }
+            ValidationErrorKind::UnknownMetaSchema { .. } => Some("$schema"),
+            // The error is at the keyword itself, rather than at a keyword of the meta-schema
+            ValidationErrorKind::KeywordNotInDraft { .. } => None,
+            ValidationErrorKind::Deprecated => Some("deprecated"),
+        }
+    }
+
+    /// A TypeMismatch between the expected type(s) and the type of the value
+    pub fn type_mismatch<S: Into<String>>(
+        expected: S,
+        data: &saphyr::YamlData<saphyr::MarkedYaml>,
+    ) -> ValidationErrorKind {
+        ValidationErrorKind::TypeMismatch {
+            expected: expected.into(),
+            found: type_name_of(data).to_string(),
+        }
+    }
+}
+
+/// Returns the JSON Schema type name of a YAML value, e.g. `integer` or `object`
+pub fn type_name_of(data: &saphyr::YamlData<saphyr::MarkedYaml>) -> &'static str {
+    match data {
+        saphyr::YamlData::Null => "null",
+        saphyr::YamlData::Boolean(_) => "boolean",
+        saphyr::YamlData::Integer(_) => "integer",
+        saphyr::YamlData::Real(_) => "number",
+        saphyr::YamlData::String(_) => "string",
+        saphyr::YamlData::Array(_) => "array",
+        saphyr::YamlData::Hash(_) => "object",
+        _ => "unknown",
     }
 }
 
@@ -65,13 +267,21 @@ impl Validator for YamlSchema {
             YamlSchema::Empty => Ok(()),
             YamlSchema::TypeNull => {
                 if !value.data.is_null() {
-                    context.add_error(value, format!("Expected null, but got: {:?}", value.data));
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::type_mismatch("null", &value.data),
+                        format!("Expected null, but got: {:?}", value.data),
+                    );
                 }
                 Ok(())
             }
             YamlSchema::BooleanLiteral(boolean) => {
                 if !*boolean {
-                    context.add_error(value, "Schema is `false`!".to_string());
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::FalseSchema,
+                        "Schema is `false`!".to_string(),
+                    );
                 }
                 Ok(())
             }
@@ -83,16 +293,29 @@ impl Validator for YamlSchema {
             YamlSchema::Number(number_schema) => number_schema.validate(context, value),
             YamlSchema::Object(object_schema) => object_schema.validate(context, value),
             YamlSchema::Array(array_schema) => array_schema.validate(context, value),
+            YamlSchema::MultipleTypes(multiple_types_schema) => {
+                multiple_types_schema.validate(context, value)
+            }
+            YamlSchema::AllOf(all_of_schema) => all_of_schema.validate(context, value),
             YamlSchema::AnyOf(any_of_schema) => any_of_schema.validate(context, value),
             YamlSchema::OneOf(one_of_schema) => one_of_schema.validate(context, value),
             YamlSchema::Not(not_schema) => not_schema.validate(context, value),
+            YamlSchema::IfThenElse(if_then_else_schema) => {
+                if_then_else_schema.validate(context, value)
+            }
+            YamlSchema::Ref(ref_schema) => ref_schema.validate(context, value),
+            YamlSchema::Annotated(annotated_schema) => annotated_schema.validate(context, value),
         }
     }
 }
 
 fn validate_boolean_schema(context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
     if !value.data.is_boolean() {
-        context.add_error(value, format!("Expected: boolean, found: {:?}", value));
+        context.add_error(
+            value,
+            ValidationErrorKind::type_mismatch("boolean", &value.data),
+            format!("Expected: boolean, found: {:?}", value),
+        );
     }
     Ok(())
 }
@@ -124,5 +347,31 @@ mod tests {
         let errors = context.errors.borrow();
         let error = errors.first().unwrap();
         assert_eq!(error.error, "Expected null, but got: String(\"value\")");
+        assert_eq!(
+            error.kind,
+            ValidationErrorKind::TypeMismatch {
+                expected: "null".to_string(),
+                found: "string".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_keyword_location() {
+        let context = Context::default();
+        let sub_context = context
+            .append_path("a/b")
+            .append_schema_path(&["properties", "a/b"]);
+        let docs = saphyr::MarkedYaml::load_from_str("value").unwrap();
+        YamlSchema::TypeNull
+            .validate(&sub_context, docs.first().unwrap())
+            .unwrap();
+        let errors = context.errors.borrow();
+        let error = errors.first().unwrap();
+        assert_eq!(error.keyword_location, "/properties/a~1b/type");
+        assert_eq!(
+            error.to_string(),
+            "[1:1] .a/b: Expected null, but got: String(\"value\") (/properties/a~1b/type)"
+        );
     }
 }
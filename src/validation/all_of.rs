@@ -0,0 +1,61 @@
+use log::debug;
+
+use super::Validator;
+use crate::Context;
+use crate::Result;
+
+impl Validator for crate::schemas::AllOfSchema {
+    /// Validate the value against every schema in `allOf` in the same context, so that the
+    /// errors of every failing schema are reported, each with its own path
+    fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
+        for (i, schema) in self.all_of.iter().enumerate() {
+            debug!(
+                "AllOf: Validating value: {:?} against schema: {}",
+                value, schema
+            );
+            if self.implicit {
+                schema.validate(context, value)?;
+            } else {
+                let sub_context = context.append_schema_path(&["allOf", &i.to_string()]);
+                schema.validate(&sub_context, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::RootSchema;
+
+    #[test]
+    fn test_all_of_reports_errors_from_every_schema() {
+        let root_schema = RootSchema::load_from_str(
+            r#"
+            allOf:
+              - type: object
+                properties:
+                  name:
+                    type: string
+              - type: object
+                properties:
+                  port:
+                    type: integer
+                    maximum: 65535
+            "#,
+        )
+        .unwrap();
+        let context = Engine::evaluate(&root_schema, "name: 42\nport: 65536", false).unwrap();
+        let errors = context.errors.borrow();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path, "name");
+        assert_eq!(errors[1].path, "port");
+        assert_eq!(errors[1].error, "Number is too big!");
+        assert_eq!(errors[0].keyword_location, "/allOf/0/properties/name/type");
+        assert_eq!(
+            errors[1].keyword_location,
+            "/allOf/1/properties/port/maximum"
+        );
+    }
+}
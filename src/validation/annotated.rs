@@ -0,0 +1,170 @@
+use log::debug;
+
+use super::Validator;
+use crate::schemas::AnnotatedSchema;
+use crate::validation::ValidationErrorKind;
+use crate::Context;
+use crate::Result;
+
+impl Validator for AnnotatedSchema {
+    /// Record the annotations for the value, warn if it's deprecated, then validate the value
+    /// against the schema
+    fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
+        debug!(
+            "Annotated: Validating value: {:?} against schema: {}",
+            value, self.schema
+        );
+        // A `$ref` with other keywords alongside it may not have any annotations
+        if !self.annotations.is_empty() {
+            context.add_annotations(&self.annotations);
+        }
+        if self.annotations.deprecated && context.warn_deprecated {
+            let message = match context.current_path.last() {
+                Some(name) => format!("'{}' is deprecated", name),
+                None => "The document is deprecated".to_string(),
+            };
+            context.add_warning(value, ValidationErrorKind::Deprecated, message);
+        }
+        self.schema.validate(context, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::EngineOptions;
+    use crate::RootSchema;
+
+    const SCHEMA: &str = r#"
+        title: Server
+        type: object
+        properties:
+          port:
+            description: The port to listen on
+            type: integer
+            default: 8080
+          host:
+            description: Use `address` instead
+            deprecated: true
+            type: string
+    "#;
+
+    #[test]
+    fn test_annotations_are_collected_for_each_instance_location() {
+        let root_schema = RootSchema::load_from_str(SCHEMA).unwrap();
+        let context = Engine::evaluate(&root_schema, "port: 80\nhost: localhost", false).unwrap();
+        assert!(!context.has_errors());
+        let root = context.annotations_at("");
+        assert_eq!(root.len(), 1);
+        assert_eq!(root[0].title.as_deref(), Some("Server"));
+        let port = context.annotations_at("/port");
+        assert_eq!(port.len(), 1);
+        assert_eq!(
+            port[0].description.as_deref(),
+            Some("The port to listen on")
+        );
+        let annotations = context.annotations.borrow();
+        assert_eq!(annotations[1].keyword_location, "/properties/port");
+        // Deprecation warnings are opt-in
+        assert!(context.warnings.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_annotations_on_array_items_have_their_own_locations() {
+        let root_schema = RootSchema::load_from_str(
+            r#"
+            type: array
+            prefixItems:
+              - description: The host
+                type: string
+            items:
+              description: A port
+              type: integer
+            "#,
+        )
+        .unwrap();
+        let context = Engine::evaluate(&root_schema, "[localhost, 80, 443]", false).unwrap();
+        assert!(!context.has_errors());
+        let host = context.annotations_at("/0");
+        assert_eq!(host.len(), 1);
+        assert_eq!(host[0].description.as_deref(), Some("The host"));
+        for location in ["/1", "/2"] {
+            let port = context.annotations_at(location);
+            assert_eq!(port.len(), 1, "{}", location);
+            assert_eq!(port[0].description.as_deref(), Some("A port"));
+        }
+        assert!(context.annotations_at("").is_empty());
+        let keyword_locations: Vec<_> = context
+            .annotations
+            .borrow()
+            .iter()
+            .map(|annotation| annotation.keyword_location.clone())
+            .collect();
+        assert_eq!(keyword_locations, ["/prefixItems/0", "/items", "/items"]);
+    }
+
+    #[test]
+    fn test_deprecated_property_warning() {
+        let root_schema = RootSchema::load_from_str(SCHEMA).unwrap();
+        let options = EngineOptions {
+            warn_deprecated: true,
+            ..Default::default()
+        };
+        let context =
+            Engine::evaluate_with_options(&root_schema, "port: 80\nhost: localhost", &options)
+                .unwrap();
+        assert!(!context.has_errors());
+        let warnings = context.warnings.borrow();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "[2:7] .host: 'host' is deprecated (/properties/host/deprecated)"
+        );
+    }
+
+    #[test]
+    fn test_deprecated_property_in_any_of_warning() {
+        let root_schema = RootSchema::load_from_str(
+            r#"
+            type: object
+            properties:
+              server:
+                anyOf:
+                  - type: object
+                    properties:
+                      host:
+                        description: Use `address` instead
+                        deprecated: true
+                        type: string
+                  - type: string
+            "#,
+        )
+        .unwrap();
+        let options = EngineOptions {
+            warn_deprecated: true,
+            ..Default::default()
+        };
+        let context =
+            Engine::evaluate_with_options(&root_schema, "server:\n  host: localhost", &options)
+                .unwrap();
+        assert!(!context.has_errors());
+        let warnings = context.warnings.borrow();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(
+            warnings[0].to_string(),
+            "[2:9] .server.host: 'host' is deprecated (/properties/server/anyOf/0/properties/host/deprecated)"
+        );
+        let host = context.annotations_at("/server/host");
+        assert_eq!(host.len(), 1);
+        assert_eq!(
+            host[0].description.as_deref(),
+            Some("Use `address` instead")
+        );
+
+        // The annotations of a branch that doesn't match aren't kept
+        let context =
+            Engine::evaluate_with_options(&root_schema, "server:\n  host: 42", &options).unwrap();
+        assert!(context.warnings.borrow().is_empty());
+        assert!(context.annotations_at("/server/host").is_empty());
+    }
+}
@@ -1,5 +1,6 @@
 use log::{debug, error};
 
+use super::ValidationErrorKind;
 use super::Validator;
 use crate::Context;
 use crate::Error;
@@ -8,18 +9,26 @@ use crate::YamlSchema;
 
 impl Validator for crate::schemas::AnyOfSchema {
     fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
-        let any_of_is_valid = validate_any_of(&self.any_of, value)?;
+        let any_of_is_valid = validate_any_of(context, &self.any_of, value)?;
         if !any_of_is_valid {
-            error!("AnyOf: None of the schemas in `oneOf` matched!");
-            context.add_error(value, "None of the schemas in `oneOf` matched!");
+            error!("AnyOf: None of the schemas in `anyOf` matched!");
+            context.add_error(
+                value,
+                ValidationErrorKind::AnyOfNoMatch,
+                "None of the schemas in `anyOf` matched!",
+            );
             fail_fast!(context);
         }
         Ok(())
     }
 }
 
-pub fn validate_any_of(schemas: &Vec<YamlSchema>, value: &saphyr::MarkedYaml) -> Result<bool> {
-    for schema in schemas {
+pub fn validate_any_of(
+    context: &Context,
+    schemas: &[YamlSchema],
+    value: &saphyr::MarkedYaml,
+) -> Result<bool> {
+    for (i, schema) in schemas.iter().enumerate() {
         debug!(
             "AnyOf: Validating value: {:?} against schema: {}",
             value, schema
@@ -27,13 +36,16 @@ pub fn validate_any_of(schemas: &Vec<YamlSchema>, value: &saphyr::MarkedYaml) ->
         // Since we're only looking for the first match, we can stop as soon as we find one
         // That also means that when evaluating sub schemas, we can fail fast to short circuit
         // the rest of the validation
-        let sub_context = Context::new(true);
+        let sub_context = context
+            .append_schema_path(&["anyOf", &i.to_string()])
+            .get_sub_context();
         let sub_result = schema.validate(&sub_context, value);
         match sub_result {
             Ok(()) | Err(Error::FailFast) => {
                 if sub_context.has_errors() {
                     continue;
                 }
+                context.keep_annotations(&sub_context);
                 return Ok(true);
             }
             Err(e) => return Err(e),
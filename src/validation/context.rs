@@ -1,21 +1,52 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use crate::validation::formats::FormatRegistry;
+use crate::validation::formats::UnknownFormat;
+use crate::validation::Annotation;
+use crate::validation::LineCol;
 use crate::validation::ValidationError;
+use crate::validation::ValidationErrorKind;
+use crate::Annotations;
+use crate::RootSchema;
 use crate::YamlSchema;
 
 /// The validation context
 #[derive(Debug, Default)]
-pub struct Context {
+pub struct Context<'r> {
+    /// The RootSchema is used to resolve references
+    pub root_schema: Option<&'r RootSchema>,
     pub current_schema: Option<Rc<YamlSchema>>,
     pub current_path: Vec<String>,
+    /// The path to the current schema, from the root schema, used to report the keyword location
+    /// of errors (e.g. `/properties/server/properties/port/maximum`)
+    pub schema_path: Vec<String>,
     pub stream_started: bool,
     pub stream_ended: bool,
+    /// The index of the document being validated in a multi-document YAML stream
+    pub document_index: usize,
     pub errors: Rc<RefCell<Vec<ValidationError>>>,
     pub fail_fast: bool,
+    /// If true, the `format` keyword is an assertion, otherwise it is only an annotation
+    pub format_assertion: bool,
+    /// Custom format validators, consulted for formats that aren't built-in
+    pub formats: Rc<FormatRegistry>,
+    /// What to do when asserting a format that is neither built-in nor registered
+    pub unknown_format: UnknownFormat,
+    /// The references currently being evaluated, and the index of the value they're being
+    /// evaluated against, used to detect circular references
+    pub active_refs: Rc<RefCell<Vec<(String, usize)>>>,
+    /// The annotations of the schemas each value was evaluated against. The annotations
+    /// collected in an isolated sub-context (e.g. for `anyOf`) are only kept if the value passes
+    /// the subschema, with `keep_annotations`.
+    pub annotations: Rc<RefCell<Vec<Annotation>>>,
+    /// Warnings that don't make the value invalid, e.g. the use of a deprecated property
+    pub warnings: Rc<RefCell<Vec<ValidationError>>>,
+    /// If true, warn when a value is at a location the schema marks as `deprecated`
+    pub warn_deprecated: bool,
 }
 
-impl Context {
+impl<'r> Context<'r> {
     /// Returns true if there are any errors in the context
     pub fn has_errors(&self) -> bool {
         !self.errors.borrow().is_empty()
@@ -26,46 +57,197 @@ impl Context {
         self.current_path.join(".")
     }
 
-    pub fn new(fail_fast: bool) -> Context {
+    /// Returns the location of a keyword in the current schema as a JSON Pointer, or the location
+    /// of the current schema itself if there's no keyword
+    pub fn keyword_location(&self, keyword: Option<&str>) -> String {
+        json_pointer(self.schema_path.iter().map(String::as_str).chain(keyword))
+    }
+
+    /// Returns the current path as a JSON Pointer, e.g. `/labels/app`
+    pub fn instance_location(&self) -> String {
+        json_pointer(self.current_path.iter().map(String::as_str))
+    }
+
+    pub fn new(fail_fast: bool) -> Context<'r> {
         Context {
             fail_fast,
             ..Default::default()
         }
     }
 
+    /// Create a new Context that can resolve references against the given RootSchema
+    pub fn with_root_schema(root_schema: &'r RootSchema, fail_fast: bool) -> Context<'r> {
+        Context {
+            root_schema: Some(root_schema),
+            current_schema: Some(root_schema.schema.clone()),
+            fail_fast,
+            ..Default::default()
+        }
+    }
+
+    /// Create an isolated sub-context, used to evaluate subschemas (e.g. for `anyOf`) without
+    /// adding their errors to this context. The sub-context always fails fast.
+    pub fn get_sub_context(&self) -> Context<'r> {
+        Context {
+            root_schema: self.root_schema,
+            current_schema: self.current_schema.clone(),
+            current_path: self.current_path.clone(),
+            schema_path: self.schema_path.clone(),
+            document_index: self.document_index,
+            fail_fast: true,
+            format_assertion: self.format_assertion,
+            formats: self.formats.clone(),
+            unknown_format: self.unknown_format,
+            active_refs: self.active_refs.clone(),
+            warn_deprecated: self.warn_deprecated,
+            ..Default::default()
+        }
+    }
+
     pub fn push_error(&self, error: ValidationError) {
         self.errors.borrow_mut().push(error);
     }
 
-    pub fn add_doc_error<V: Into<String>>(&self, error: V) {
+    pub fn add_doc_error<V: Into<String>>(&self, kind: ValidationErrorKind, error: V) {
         let path = self.path();
+        let keyword_location = self.keyword_location(kind.keyword());
         self.push_error(ValidationError {
             path,
+            instance_location: self.instance_location(),
+            keyword_location,
+            document_index: self.document_index,
             line_col: None,
+            end_line_col: None,
+            kind,
             error: error.into(),
         });
     }
 
-    pub fn add_error<V: Into<String>>(&self, marked_yaml: &saphyr::MarkedYaml, error: V) {
+    pub fn add_error<V: Into<String>>(
+        &self,
+        marked_yaml: &saphyr::MarkedYaml,
+        kind: ValidationErrorKind,
+        error: V,
+    ) {
+        self.push_error(self.error_at(marked_yaml, kind, error));
+    }
+
+    /// Add a warning about the value, which doesn't make it invalid
+    pub fn add_warning<V: Into<String>>(
+        &self,
+        marked_yaml: &saphyr::MarkedYaml,
+        kind: ValidationErrorKind,
+        error: V,
+    ) {
+        let warning = self.error_at(marked_yaml, kind, error);
+        self.warnings.borrow_mut().push(warning);
+    }
+
+    /// Record the annotations of the current schema for the current value
+    pub fn add_annotations(&self, annotations: &Annotations) {
+        self.annotations.borrow_mut().push(Annotation {
+            instance_location: self.instance_location(),
+            keyword_location: self.keyword_location(None),
+            document_index: self.document_index,
+            annotations: annotations.clone(),
+        });
+    }
+
+    /// Keep the annotations and warnings collected in a sub-context, once the value has passed
+    /// the subschema it was evaluated against
+    pub fn keep_annotations(&self, sub_context: &Context) {
+        self.annotations
+            .borrow_mut()
+            .extend(sub_context.annotations.take());
+        self.warnings
+            .borrow_mut()
+            .extend(sub_context.warnings.take());
+    }
+
+    /// Returns the annotations collected for the value at the given instance location, e.g.
+    /// `/spec/replicas`, in the order the schemas were evaluated
+    pub fn annotations_at(&self, instance_location: &str) -> Vec<Annotations> {
+        self.annotations
+            .borrow()
+            .iter()
+            .filter(|annotation| annotation.instance_location == instance_location)
+            .map(|annotation| annotation.annotations.clone())
+            .collect()
+    }
+
+    fn error_at<V: Into<String>>(
+        &self,
+        marked_yaml: &saphyr::MarkedYaml,
+        kind: ValidationErrorKind,
+        error: V,
+    ) -> ValidationError {
         let path = self.path();
-        self.push_error(ValidationError {
+        let keyword_location = self.keyword_location(kind.keyword());
+        ValidationError {
             path,
+            instance_location: self.instance_location(),
+            keyword_location,
+            document_index: self.document_index,
             line_col: Some(marked_yaml.into()),
+            end_line_col: Some(LineCol::end_of(marked_yaml)),
+            kind,
             error: error.into(),
-        });
+        }
     }
 
     /// Append a path to the current path
-    pub fn append_path<V: Into<String>>(&self, path: V) -> Context {
+    pub fn append_path<V: Into<String>>(&self, path: V) -> Context<'r> {
         let mut new_path = self.current_path.clone();
         new_path.push(path.into());
         Context {
+            root_schema: self.root_schema,
             current_schema: self.current_schema.clone(),
             current_path: new_path,
+            schema_path: self.schema_path.clone(),
+            document_index: self.document_index,
+            errors: self.errors.clone(),
+            fail_fast: self.fail_fast,
+            format_assertion: self.format_assertion,
+            formats: self.formats.clone(),
+            unknown_format: self.unknown_format,
+            stream_ended: self.stream_ended,
+            stream_started: self.stream_started,
+            active_refs: self.active_refs.clone(),
+            annotations: self.annotations.clone(),
+            warnings: self.warnings.clone(),
+            warn_deprecated: self.warn_deprecated,
+        }
+    }
+
+    /// Append segments to the schema path, e.g. `["properties", "port"]`, when descending into
+    /// a subschema
+    pub fn append_schema_path(&self, segments: &[&str]) -> Context<'r> {
+        let mut schema_path = self.schema_path.clone();
+        schema_path.extend(segments.iter().map(|segment| segment.to_string()));
+        Context {
+            root_schema: self.root_schema,
+            current_schema: self.current_schema.clone(),
+            current_path: self.current_path.clone(),
+            schema_path,
+            document_index: self.document_index,
             errors: self.errors.clone(),
             fail_fast: self.fail_fast,
+            format_assertion: self.format_assertion,
+            formats: self.formats.clone(),
+            unknown_format: self.unknown_format,
             stream_ended: self.stream_ended,
             stream_started: self.stream_started,
+            active_refs: self.active_refs.clone(),
+            annotations: self.annotations.clone(),
+            warnings: self.warnings.clone(),
+            warn_deprecated: self.warn_deprecated,
         }
     }
 }
+
+/// Joins path segments into a JSON Pointer, escaping `~` and `/` in each segment
+fn json_pointer<'a>(segments: impl Iterator<Item = &'a str>) -> String {
+    segments
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
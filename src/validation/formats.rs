@@ -0,0 +1,309 @@
+/// Built-in and custom validators for the `format` keyword
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+/// A format validator returns true if the string is valid for the format
+pub type FormatValidator = fn(&str) -> bool;
+
+/// A custom format validator returns an error message if the string is not valid for the format
+pub type CustomFormatValidator = Rc<dyn Fn(&str) -> std::result::Result<(), String>>;
+
+/// What to do when asserting a format that is neither built-in nor registered
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum UnknownFormat {
+    /// Unknown formats are ignored, and only annotate the value
+    #[default]
+    Ignore,
+    /// Unknown formats are reported as validation errors
+    Error,
+}
+
+/// A registry of custom format validators, consulted for formats that aren't built-in
+#[derive(Clone, Default)]
+pub struct FormatRegistry {
+    validators: HashMap<String, CustomFormatValidator>,
+}
+
+impl FormatRegistry {
+    pub fn new() -> FormatRegistry {
+        FormatRegistry::default()
+    }
+
+    /// Register a custom validator for the named format, replacing any existing one
+    pub fn register<N, F>(&mut self, name: N, validator: F)
+    where
+        N: Into<String>,
+        F: Fn(&str) -> std::result::Result<(), String> + 'static,
+    {
+        self.validators.insert(name.into(), Rc::new(validator));
+    }
+
+    /// Returns the custom validator registered for the named format, if any
+    pub fn get(&self, name: &str) -> Option<&CustomFormatValidator> {
+        self.validators.get(name)
+    }
+}
+
+impl std::fmt::Debug for FormatRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.validators.keys()).finish()
+    }
+}
+
+/// Returns the built-in validator for a format, or None if the format is unknown
+pub fn builtin_format(format: &str) -> Option<FormatValidator> {
+    match format {
+        "date" => Some(is_date),
+        "date-time" => Some(is_date_time),
+        "time" => Some(is_time),
+        "duration" => Some(is_duration),
+        "email" => Some(is_email),
+        "hostname" => Some(is_hostname),
+        "ipv4" => Some(is_ipv4),
+        "ipv6" => Some(is_ipv6),
+        "uri" => Some(is_uri),
+        "uri-reference" => Some(is_uri_reference),
+        "uuid" => Some(is_uuid),
+        "regex" => Some(is_regex),
+        "json-pointer" => Some(is_json_pointer),
+        _ => None,
+    }
+}
+
+/// Compiles a regular expression once, on first use
+macro_rules! static_regex {
+    ($re:literal) => {{
+        static REGEX: OnceLock<Regex> = OnceLock::new();
+        REGEX.get_or_init(|| Regex::new($re).unwrap())
+    }};
+}
+
+/// A full-date as defined by RFC 3339, e.g. `2024-02-29`
+pub fn is_date(s: &str) -> bool {
+    // `\d` would also match non-ASCII digits, which RFC 3339 doesn't allow
+    let Some(captures) = static_regex!(r"^([0-9]{4})-([0-9]{2})-([0-9]{2})$").captures(s) else {
+        return false;
+    };
+    let field = |i: usize| captures[i].parse::<u32>().ok();
+    let (Some(year), Some(month), Some(day)) = (field(1), field(2), field(3)) else {
+        return false;
+    };
+    let is_leap_year =
+        (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+/// A full-time as defined by RFC 3339, e.g. `23:59:59.999Z` or `08:30:00+08:00`
+pub fn is_time(s: &str) -> bool {
+    let Some(captures) = static_regex!(
+        r"^([0-9]{2}):([0-9]{2}):([0-9]{2})(\.[0-9]+)?([zZ]|[+-]([0-9]{2}):([0-9]{2}))$"
+    )
+    .captures(s) else {
+        return false;
+    };
+    let field = |i: usize| captures.get(i).and_then(|m| m.as_str().parse::<u32>().ok());
+    let (Some(hour), Some(minute), Some(second)) = (field(1), field(2), field(3)) else {
+        return false;
+    };
+    // The offset is only captured if it isn't `Z`
+    let offset_is_valid = match (field(6), field(7)) {
+        (Some(hours), Some(minutes)) => hours <= 23 && minutes <= 59,
+        _ => true,
+    };
+    // 60 allows for leap seconds
+    hour <= 23 && minute <= 59 && second <= 60 && offset_is_valid
+}
+
+/// A date-time as defined by RFC 3339, e.g. `2024-02-29T23:59:59Z`
+pub fn is_date_time(s: &str) -> bool {
+    match s.split_once(['T', 't']) {
+        Some((date, time)) => is_date(date) && is_time(time),
+        None => false,
+    }
+}
+
+/// A duration as defined by ISO 8601, e.g. `P3Y6M4DT12H30M5S` or `P4W`
+pub fn is_duration(s: &str) -> bool {
+    let re = static_regex!(
+        r"^P([0-9]+Y)?([0-9]+M)?([0-9]+W)?([0-9]+D)?(T([0-9]+H)?([0-9]+M)?([0-9]+(\.[0-9]+)?S)?)?$"
+    );
+    // At least one component is required, and `T` must be followed by a time component
+    re.is_match(s) && s != "P" && !s.ends_with('T')
+}
+
+/// An email address, e.g. `user@example.com`
+pub fn is_email(s: &str) -> bool {
+    match s.rsplit_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && local.len() <= 64
+                && static_regex!(r"^[A-Za-z0-9!#$%&'*+/=?^_`{|}~.-]+$").is_match(local)
+                && !local.starts_with('.')
+                && !local.ends_with('.')
+                && !local.contains("..")
+                && match domain
+                    .strip_prefix('[')
+                    .and_then(|domain| domain.strip_suffix(']'))
+                {
+                    Some(ip) => is_ipv4(ip) || ip.strip_prefix("IPv6:").is_some_and(is_ipv6),
+                    None => is_hostname(domain),
+                }
+        }
+        None => false,
+    }
+}
+
+/// A hostname as defined by RFC 1123, e.g. `www.example.com`
+pub fn is_hostname(s: &str) -> bool {
+    let hostname = s.strip_suffix('.').unwrap_or(s);
+    !hostname.is_empty()
+        && hostname.len() <= 253
+        && hostname.split('.').all(|label| {
+            static_regex!(r"^[A-Za-z0-9]([A-Za-z0-9-]{0,61}[A-Za-z0-9])?$").is_match(label)
+        })
+}
+
+/// An IPv4 address in dotted-quad notation, e.g. `192.168.0.1`
+pub fn is_ipv4(s: &str) -> bool {
+    s.parse::<Ipv4Addr>().is_ok()
+}
+
+/// An IPv6 address as defined by RFC 4291, e.g. `::1`
+pub fn is_ipv6(s: &str) -> bool {
+    s.parse::<Ipv6Addr>().is_ok()
+}
+
+/// A URI as defined by RFC 3986, which must have a scheme, e.g. `https://example.com/path`
+pub fn is_uri(s: &str) -> bool {
+    static_regex!(r"^[A-Za-z][A-Za-z0-9+.-]*:").is_match(s) && is_uri_reference(s)
+}
+
+/// A URI or a relative reference as defined by RFC 3986, e.g. `../path?query#fragment`
+pub fn is_uri_reference(s: &str) -> bool {
+    // Only unreserved, reserved and percent-encoded characters are allowed
+    static_regex!(r"^([A-Za-z0-9\-._~:/?#\[\]@!$&'()*+,;=]|%[0-9A-Fa-f]{2})*$").is_match(s)
+        && s.matches('#').count() <= 1
+}
+
+/// A UUID as defined by RFC 4122, e.g. `f81d4fae-7dec-11d0-a765-00a0c91e6bf6`
+pub fn is_uuid(s: &str) -> bool {
+    static_regex!(r"^[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}$")
+        .is_match(s)
+}
+
+/// A regular expression that can be compiled
+pub fn is_regex(s: &str) -> bool {
+    Regex::new(s).is_ok()
+}
+
+/// A JSON Pointer as defined by RFC 6901, e.g. `/foo/0/bar~1baz`
+pub fn is_json_pointer(s: &str) -> bool {
+    static_regex!(r"^(/([^/~]|~[01])*)*$").is_match(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date() {
+        assert!(is_date("2024-02-29"));
+        assert!(!is_date("2023-02-29"));
+        assert!(!is_date("2024-13-01"));
+        assert!(!is_date("2024-1-01"));
+    }
+
+    #[test]
+    fn test_date_time_and_time() {
+        assert!(is_date_time("2024-02-29T23:59:59Z"));
+        assert!(is_date_time("2024-02-29T08:30:00.123+08:00"));
+        assert!(!is_date_time("2024-02-29 23:59:59Z"));
+        assert!(!is_date_time("2024-02-29T24:00:00Z"));
+        assert!(is_time("23:59:60Z"));
+        assert!(!is_time("23:59:59"));
+    }
+
+    #[test]
+    fn test_duration() {
+        assert!(is_duration("P3Y6M4DT12H30M5S"));
+        assert!(is_duration("P4W"));
+        assert!(is_duration("PT0.5S"));
+        assert!(!is_duration("P"));
+        assert!(!is_duration("P1DT"));
+        assert!(!is_duration("1D"));
+    }
+
+    #[test]
+    fn test_email_and_hostname() {
+        assert!(is_email("user@example.com"));
+        assert!(is_email("first.last+tag@[192.168.0.1]"));
+        assert!(!is_email("user@"));
+        assert!(!is_email("user.@example.com"));
+        assert!(!is_email("user example.com"));
+        assert!(is_hostname("www.example.com"));
+        assert!(!is_hostname("-example.com"));
+        assert!(!is_hostname("example..com"));
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        assert!(is_ipv4("192.168.0.1"));
+        assert!(!is_ipv4("256.0.0.1"));
+        assert!(is_ipv6("::1"));
+        assert!(is_ipv6("2001:db8::8a2e:370:7334"));
+        assert!(!is_ipv6("192.168.0.1"));
+    }
+
+    #[test]
+    fn test_uris() {
+        assert!(is_uri("https://example.com/path?query=1#fragment"));
+        assert!(!is_uri("/relative/path"));
+        assert!(is_uri_reference("../relative/path#fragment"));
+        assert!(!is_uri_reference("has spaces"));
+        assert!(!is_uri_reference("a#b#c"));
+    }
+
+    #[test]
+    fn test_uuid_regex_and_json_pointer() {
+        assert!(is_uuid("f81d4fae-7dec-11d0-a765-00a0c91e6bf6"));
+        assert!(!is_uuid("f81d4fae7dec11d0a76500a0c91e6bf6"));
+        assert!(is_regex("^[a-z]+$"));
+        assert!(!is_regex("(unclosed"));
+        assert!(is_json_pointer(""));
+        assert!(is_json_pointer("/foo/0/bar~1baz"));
+        assert!(!is_json_pointer("foo"));
+        assert!(!is_json_pointer("/foo~2"));
+    }
+
+    #[test]
+    fn test_builtin_format() {
+        assert!(builtin_format("email").is_some());
+        assert!(builtin_format("semver").is_none());
+    }
+
+    #[test]
+    fn test_format_registry() {
+        let mut registry = FormatRegistry::new();
+        registry.register("semver", |s: &str| match s.split('.').count() {
+            3 => Ok(()),
+            _ => Err("expected MAJOR.MINOR.PATCH".to_string()),
+        });
+        let semver = registry.get("semver").unwrap();
+        assert!(semver("1.2.3").is_ok());
+        assert_eq!(semver("1.2").unwrap_err(), "expected MAJOR.MINOR.PATCH");
+        assert!(registry.get("cron").is_none());
+        assert_eq!(format!("{:?}", registry), "{\"semver\"}");
+    }
+}
@@ -0,0 +1,34 @@
+use log::debug;
+
+use super::Validator;
+use crate::schemas::IfThenElseSchema;
+use crate::Context;
+use crate::Error;
+use crate::Result;
+
+impl Validator for IfThenElseSchema {
+    fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
+        // Like `anyOf`, the `if` schema is evaluated in an isolated context, since its errors
+        // only decide which branch applies and are never reported
+        let sub_context = context.append_schema_path(&["if"]).get_sub_context();
+        let if_is_valid = match self.r#if.validate(&sub_context, value) {
+            Ok(()) | Err(Error::FailFast) => !sub_context.has_errors(),
+            Err(e) => return Err(e),
+        };
+        debug!("IfThenElse: `if` is valid: {}", if_is_valid);
+        if if_is_valid {
+            context.keep_annotations(&sub_context);
+        }
+        // The `then` or `else` schema is evaluated against the same value and context, so its
+        // errors are reported as if the branch had been declared in place of the conditional
+        let (keyword, branch) = if if_is_valid {
+            ("then", &self.then)
+        } else {
+            ("else", &self.r#else)
+        };
+        if let Some(schema) = branch {
+            schema.validate(&context.append_schema_path(&[keyword]), value)?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::schemas::NotSchema;
 use crate::validation::Context;
+use crate::validation::ValidationErrorKind;
 use crate::validation::Validator;
 use crate::Result;
 use log::debug;
@@ -12,14 +13,18 @@ impl Validator for NotSchema {
         );
 
         // Create a sub-context to validate against the inner schema
-        let sub_context = Context::new(true);
+        let sub_context = context.get_sub_context();
         let sub_result = self.not.validate(&sub_context, value);
 
         match sub_result {
             Ok(()) | Err(crate::Error::FailFast) => {
                 // If the inner schema validates successfully, then this is an error for 'not'
                 if !sub_context.has_errors() {
-                    context.add_error(value, "Value matches schema in `not`");
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::NotMatched,
+                        "Value matches schema in `not`",
+                    );
                     fail_fast!(context);
                 }
             }
@@ -6,6 +6,8 @@ use crate::format_marker;
 use crate::schemas::BoolOrTypedSchema;
 use crate::schemas::ObjectSchema;
 use crate::validation::Context;
+use crate::validation::LineCol;
+use crate::validation::ValidationErrorKind;
 use crate::Error;
 use crate::Result;
 use crate::Validator;
@@ -19,7 +21,11 @@ impl Validator for ObjectSchema {
         match data {
             saphyr::YamlData::Hash(hash) => self.validate_object_mapping(context, value, hash),
             other => {
-                context.add_error(value, format!("Expected an object, but got: {:#?}", other));
+                context.add_error(
+                    value,
+                    ValidationErrorKind::type_mismatch("object", other),
+                    format!("Expected an object, but got: {:#?}", other),
+                );
                 Ok(())
             }
         }
@@ -32,7 +38,9 @@ pub fn try_validate_value_against_properties(
     value: &saphyr::MarkedYaml,
     properties: &HashMap<String, YamlSchema>,
 ) -> Result<bool> {
-    let sub_context = context.append_path(key);
+    let sub_context = context
+        .append_path(key)
+        .append_schema_path(&["properties", key]);
     if let Some(schema) = properties.get(key) {
         debug!("Validating property '{}' with schema: {}", key, schema);
         let result = schema.validate(&sub_context, value);
@@ -53,7 +61,9 @@ pub fn try_validate_value_against_additional_properties(
     value: &saphyr::MarkedYaml,
     additional_properties: &BoolOrTypedSchema,
 ) -> Result<bool> {
-    let sub_context = context.append_path(key);
+    let sub_context = context
+        .append_path(key)
+        .append_schema_path(&["additionalProperties"]);
 
     match additional_properties {
         // if additional_properties: true, then any additional properties are allowed
@@ -62,6 +72,9 @@ pub fn try_validate_value_against_additional_properties(
         BoolOrTypedSchema::Boolean(false) => {
             context.add_error(
                 value,
+                ValidationErrorKind::AdditionalPropertyNotAllowed {
+                    property: key.clone(),
+                },
                 format!("Additional property '{}' is not allowed!", key),
             );
             // returning `false` signals fail fast
@@ -71,6 +84,12 @@ pub fn try_validate_value_against_additional_properties(
         BoolOrTypedSchema::TypedSchema(schema) => {
             schema.validate(&sub_context, value)?;
         }
+        BoolOrTypedSchema::Reference(ref_schema) => {
+            ref_schema.validate(&sub_context, value)?;
+        }
+        BoolOrTypedSchema::Annotated(annotated_schema) => {
+            annotated_schema.validate(&sub_context, value)?;
+        }
     }
     Ok(true)
 }
@@ -104,16 +123,8 @@ impl ObjectSchema {
                 }
             }
 
-            // Then, we check if additional properties are allowed or not
-            if let Some(additional_properties) = &self.additional_properties {
-                try_validate_value_against_additional_properties(
-                    context,
-                    &key,
-                    value,
-                    additional_properties,
-                )?;
-            }
             // Then we check if pattern_properties matches
+            let mut matches_pattern = false;
             if let Some(pattern_properties) = &self.pattern_properties {
                 for (pattern, schema) in pattern_properties {
                     // TODO: compile the regex once instead of every time we're evaluating
@@ -121,10 +132,26 @@ impl ObjectSchema {
                         Error::GenericError(format!("Invalid regular expression pattern: {}", e))
                     })?;
                     if re.is_match(key.as_str()) {
-                        schema.validate(context, value)?;
+                        matches_pattern = true;
+                        let sub_context = context
+                            .append_path(&key)
+                            .append_schema_path(&["patternProperties", pattern]);
+                        schema.validate(&sub_context, value)?;
                     }
                 }
             }
+            // Then, we check if additional properties are allowed or not, which only applies to
+            // the properties that didn't match `properties` or `patternProperties`
+            if let Some(additional_properties) = &self.additional_properties {
+                if !matches_pattern {
+                    try_validate_value_against_additional_properties(
+                        context,
+                        &key,
+                        value,
+                        additional_properties,
+                    )?;
+                }
+            }
             // Finally, we check if it matches property_names
             if let Some(property_names) = &self.property_names {
                 let re = regex::Regex::new(property_names).map_err(|e| {
@@ -134,6 +161,10 @@ impl ObjectSchema {
                 if !re.is_match(key.as_str()) {
                     context.add_error(
                         k,
+                        ValidationErrorKind::PropertyNameMismatch {
+                            property: key.clone(),
+                            pattern: re.as_str().to_string(),
+                        },
                         format!(
                             "Property name '{}' does not match pattern '{}'",
                             key,
@@ -150,11 +181,13 @@ impl ObjectSchema {
             for required_property in required {
                 if !mapping
                     .keys()
-                    .map(|k| k.data.as_str().unwrap())
-                    .any(|s| s == required_property)
+                    .any(|k| k.data.as_str() == Some(required_property.as_str()))
                 {
                     context.add_error(
                         object,
+                        ValidationErrorKind::RequiredMissing {
+                            property: required_property.clone(),
+                        },
                         format!("Required property '{}' is missing!", required_property),
                     );
                     fail_fast!(context)
@@ -162,11 +195,23 @@ impl ObjectSchema {
             }
         }
 
+        // Validate dependentRequired and dependentSchemas, reporting errors at the property that
+        // triggered the dependency
+        for k in mapping.keys() {
+            if let Some(key) = k.data.as_str() {
+                self.validate_dependencies(context, object, mapping, k, key)?;
+            }
+        }
+
         // Validate minProperties
         if let Some(min_properties) = &self.min_properties {
             if mapping.len() < *min_properties {
                 context.add_error(
                     object,
+                    ValidationErrorKind::MinProperties {
+                        limit: *min_properties,
+                        actual: mapping.len(),
+                    },
                     format!(
                         "Object has too few properties! Minimum is {}!",
                         min_properties
@@ -180,6 +225,10 @@ impl ObjectSchema {
             if mapping.len() > *max_properties {
                 context.add_error(
                     object,
+                    ValidationErrorKind::MaxProperties {
+                        limit: *max_properties,
+                        actual: mapping.len(),
+                    },
                     format!(
                         "Object has too many properties! Maximum is {}!",
                         max_properties
@@ -191,6 +240,71 @@ impl ObjectSchema {
 
         Ok(())
     }
+
+    fn validate_dependencies(
+        &self,
+        context: &Context,
+        object: &saphyr::MarkedYaml,
+        mapping: &saphyr::AnnotatedHash<saphyr::MarkedYaml>,
+        k: &saphyr::MarkedYaml,
+        key: &str,
+    ) -> Result<()> {
+        if let Some(dependent_required) = &self.dependent_required {
+            if let Some(required) = dependent_required.get(key) {
+                for required_property in required {
+                    if !mapping
+                        .keys()
+                        .any(|k| k.data.as_str() == Some(required_property.as_str()))
+                    {
+                        context.add_error(
+                            k,
+                            ValidationErrorKind::DependentRequiredMissing {
+                                property: required_property.clone(),
+                                dependent_on: key.to_string(),
+                            },
+                            format!(
+                                "Property '{}' is required when '{}' is present!",
+                                required_property, key
+                            ),
+                        );
+                        fail_fast!(context)
+                    }
+                }
+            }
+        }
+        if let Some(dependent_schemas) = &self.dependent_schemas {
+            if let Some(schema) = dependent_schemas.get(key) {
+                debug!("Validating dependent schema for '{}': {}", key, schema);
+                // Validate in a separate context, so that errors about the object as a whole
+                // (such as missing required properties) can be moved to the triggering key
+                let sub_context = Context {
+                    errors: Default::default(),
+                    fail_fast: context.fail_fast,
+                    ..context
+                        .append_schema_path(&["dependentSchemas", key])
+                        .get_sub_context()
+                };
+                let result = schema.validate(&sub_context, object);
+                if !sub_context.has_errors() {
+                    context.keep_annotations(&sub_context);
+                }
+                let object_line_col: LineCol = object.into();
+                for mut error in sub_context.errors.take() {
+                    if error.line_col.as_ref() == Some(&object_line_col) {
+                        error.line_col = Some(k.into());
+                        error.end_line_col = Some(LineCol::end_of(k));
+                    }
+                    context.push_error(error);
+                }
+                match result {
+                    Ok(()) => (),
+                    Err(Error::FailFast) => fail_fast!(context),
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
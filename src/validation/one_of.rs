@@ -1,5 +1,6 @@
 use log::{debug, error};
 
+use super::ValidationErrorKind;
 use super::Validator;
 use crate::Context;
 use crate::Error;
@@ -11,7 +12,11 @@ impl Validator for crate::schemas::OneOfSchema {
         let one_of_is_valid = validate_one_of(context, &self.one_of, value)?;
         if !one_of_is_valid {
             error!("OneOf: None of the schemas in `oneOf` matched!");
-            context.add_error(value, "None of the schemas in `oneOf` matched!");
+            context.add_error(
+                value,
+                ValidationErrorKind::OneOfNoMatch,
+                "None of the schemas in `oneOf` matched!",
+            );
             fail_fast!(context);
         }
         Ok(())
@@ -20,16 +25,18 @@ impl Validator for crate::schemas::OneOfSchema {
 
 pub fn validate_one_of(
     context: &Context,
-    schemas: &Vec<YamlSchema>,
+    schemas: &[YamlSchema],
     value: &saphyr::MarkedYaml,
 ) -> Result<bool> {
     let mut one_of_is_valid = false;
-    for schema in schemas {
+    for (i, schema) in schemas.iter().enumerate() {
         debug!(
             "OneOf: Validating value: {:?} against schema: {}",
             value, schema
         );
-        let sub_context = Context::new(true);
+        let sub_context = context
+            .append_schema_path(&["oneOf", &i.to_string()])
+            .get_sub_context();
         let sub_result = schema.validate(&sub_context, value);
         match sub_result {
             Ok(()) | Err(Error::FailFast) => {
@@ -40,10 +47,15 @@ pub fn validate_one_of(
                 if sub_context.has_errors() {
                     continue;
                 }
+                context.keep_annotations(&sub_context);
 
                 if one_of_is_valid {
                     error!("OneOf: Value matched multiple schemas in `oneOf`!");
-                    context.add_error(value, "Value matched multiple schemas in `oneOf`!");
+                    context.add_error(
+                        value,
+                        ValidationErrorKind::OneOfMultipleMatches,
+                        "Value matched multiple schemas in `oneOf`!",
+                    );
                     fail_fast!(context);
                 } else {
                     one_of_is_valid = true;
@@ -0,0 +1,47 @@
+use log::debug;
+
+use crate::schemas::RefSchema;
+use crate::validation::Context;
+use crate::validation::Validator;
+use crate::Error;
+use crate::Result;
+
+impl Validator for RefSchema {
+    /// Resolve the reference against the root schema, then validate the value against the
+    /// referenced schema in the same context
+    fn validate(&self, context: &Context, value: &saphyr::MarkedYaml) -> Result<()> {
+        let root_schema = context
+            .root_schema
+            .ok_or_else(|| generic_error!("Cannot resolve {} without a root schema", self.r#ref))?;
+        let schema = root_schema
+            .resolve(&self.r#ref)
+            .ok_or_else(|| generic_error!("Cannot resolve reference: {}", self.r#ref))?;
+        debug!("[RefSchema] Resolved {} to: {}", self.r#ref, schema);
+
+        // Evaluating the same reference against the same value again, before the first
+        // evaluation has finished, would recurse forever
+        let active_ref = (self.r#ref.clone(), value.span.start.index());
+        if context.active_refs.borrow().contains(&active_ref) {
+            return Err(Error::CircularReference(self.r#ref.clone()));
+        }
+        context.active_refs.borrow_mut().push(active_ref);
+        let result = schema.validate(&context.append_schema_path(&["$ref"]), value);
+        context.active_refs.borrow_mut().pop();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Engine;
+    use crate::RootSchema;
+
+    use super::*;
+
+    #[test]
+    fn test_circular_reference_across_files() {
+        let root_schema = RootSchema::load_file("tests/fixtures/refs/cycle-a.yaml").unwrap();
+        let result = Engine::evaluate(&root_schema, "foo: bar", false);
+        assert!(matches!(result, Err(Error::CircularReference(_))));
+    }
+}
@@ -4,6 +4,9 @@ use crate::Context;
 use crate::Result;
 use crate::StringSchema;
 
+use super::formats::builtin_format;
+use super::formats::UnknownFormat;
+use super::ValidationErrorKind;
 use super::Validator;
 
 impl Validator for StringSchema {
@@ -15,45 +18,106 @@ impl Validator for StringSchema {
             value,
         );
         if !errors.is_empty() {
-            for error in errors {
-                context.add_error(value, error);
+            for (kind, error) in errors {
+                context.add_error(value, kind, error);
+            }
+        }
+        if let (Some(format), Some(s)) = (&self.format, value.data.as_str()) {
+            if context.format_assertion {
+                validate_format(context, format, s, value);
             }
         }
         Ok(())
     }
 }
 
+/// Assert that a string is valid for the given format, using the built-in validators first,
+/// then any custom validators registered in the context
+fn validate_format(context: &Context, format: &str, s: &str, value: &saphyr::MarkedYaml) {
+    if let Some(is_valid) = builtin_format(format) {
+        if !is_valid(s) {
+            context.add_error(
+                value,
+                ValidationErrorKind::FormatMismatch {
+                    format: format.to_string(),
+                },
+                format!("String is not a valid {}!", format),
+            );
+        }
+    } else if let Some(validator) = context.formats.get(format) {
+        if let Err(e) = validator(s) {
+            context.add_error(
+                value,
+                ValidationErrorKind::FormatMismatch {
+                    format: format.to_string(),
+                },
+                format!("String is not a valid {}: {}", format, e),
+            );
+        }
+    } else if context.unknown_format == UnknownFormat::Error {
+        context.add_error(
+            value,
+            ValidationErrorKind::UnknownFormat {
+                format: format.to_string(),
+            },
+            format!("Unknown format: {}!", format),
+        );
+    }
+}
+
 /// Just trying to isolate the actual validation into a function that doesn't take a context
+///
+/// Returns the kind and message of each error
 pub fn validate_string(
     min_length: Option<usize>,
     max_length: Option<usize>,
     pattern: Option<&Regex>,
     value: &saphyr::MarkedYaml,
-) -> Vec<String> {
+) -> Vec<(ValidationErrorKind, String)> {
     let mut errors = Vec::new();
     let data = &value.data;
     let yaml_string = match data.as_str() {
         Some(s) => s,
         None => {
-            errors.push(format!("Expected a string, but got: {:?}", data));
+            errors.push((
+                ValidationErrorKind::type_mismatch("string", data),
+                format!("Expected a string, but got: {:?}", data),
+            ));
             return errors;
         }
     };
     if let Some(min_length) = min_length {
         if yaml_string.len() < min_length {
-            errors.push(format!("String is too short! (min length: {})", min_length));
+            errors.push((
+                ValidationErrorKind::MinLength {
+                    limit: min_length,
+                    actual: yaml_string.len(),
+                },
+                format!("String is too short! (min length: {})", min_length),
+            ));
         }
     }
     if let Some(max_length) = max_length {
         if yaml_string.len() > max_length {
-            errors.push(format!("String is too long! (max length: {})", max_length));
+            errors.push((
+                ValidationErrorKind::MaxLength {
+                    limit: max_length,
+                    actual: yaml_string.len(),
+                },
+                format!("String is too long! (max length: {})", max_length),
+            ));
         }
     }
     if let Some(regex) = pattern {
         if !regex.is_match(yaml_string) {
-            errors.push(format!(
-                "String does not match regular expression {}!",
-                regex.as_str()
+            errors.push((
+                ValidationErrorKind::PatternMismatch {
+                    pattern: regex.as_str().to_string(),
+                },
+                format!(
+                    "String does not match regular expression {}!",
+                    regex.as_str()
+                ),
             ));
         }
     }
@@ -63,6 +127,7 @@ pub fn validate_string(
 #[cfg(test)]
 mod tests {
     use crate::Engine;
+    use crate::EngineOptions;
     use crate::RootSchema;
     use crate::YamlSchema;
 
@@ -89,6 +154,61 @@ mod tests {
         assert!(context.has_errors());
     }
 
+    #[test]
+    fn test_engine_validate_string_with_format() {
+        let schema = StringSchema {
+            format: Some("email".to_string()),
+            ..Default::default()
+        };
+        let root_schema = RootSchema::new(YamlSchema::String(schema));
+        let context = Engine::evaluate(&root_schema, "not an email", false).unwrap();
+        assert!(!context.has_errors());
+        let options = EngineOptions {
+            format_assertion: true,
+            ..Default::default()
+        };
+        let context =
+            Engine::evaluate_with_options(&root_schema, "user@example.com", &options).unwrap();
+        assert!(!context.has_errors());
+        let context =
+            Engine::evaluate_with_options(&root_schema, "not an email", &options).unwrap();
+        let errors = context.errors.borrow();
+        assert_eq!(
+            errors.first().unwrap().error,
+            "String is not a valid email!"
+        );
+    }
+
+    #[test]
+    fn test_engine_validate_string_with_custom_format() {
+        let schema = StringSchema {
+            format: Some("semver".to_string()),
+            ..Default::default()
+        };
+        let root_schema = RootSchema::new(YamlSchema::String(schema));
+        let mut options = EngineOptions {
+            format_assertion: true,
+            unknown_format: UnknownFormat::Error,
+            ..Default::default()
+        };
+        let context = Engine::evaluate_with_options(&root_schema, "1.2.3", &options).unwrap();
+        assert_eq!(
+            context.errors.borrow().first().unwrap().error,
+            "Unknown format: semver!"
+        );
+        options.register_format("semver", |s: &str| match s.split('.').count() {
+            3 => Ok(()),
+            _ => Err("expected MAJOR.MINOR.PATCH".to_string()),
+        });
+        let context = Engine::evaluate_with_options(&root_schema, "1.2.3", &options).unwrap();
+        assert!(!context.has_errors());
+        let context = Engine::evaluate_with_options(&root_schema, "\"1.2\"", &options).unwrap();
+        assert_eq!(
+            context.errors.borrow().first().unwrap().error,
+            "String is not a valid semver: expected MAJOR.MINOR.PATCH"
+        );
+    }
+
     #[test]
     fn test_validate_string() {
         let docs = saphyr::MarkedYaml::load_from_str("hello").unwrap();
@@ -105,8 +225,15 @@ mod tests {
         let docs = saphyr::MarkedYaml::load_from_str("hell").unwrap();
         let errors = validate_string(Some(5), None, None, docs.first().unwrap());
         assert!(!errors.is_empty());
-        let first = errors.first().unwrap();
-        assert_eq!(first, "String is too short! (min length: 5)");
+        let (kind, error) = errors.first().unwrap();
+        assert_eq!(
+            *kind,
+            ValidationErrorKind::MinLength {
+                limit: 5,
+                actual: 4
+            }
+        );
+        assert_eq!(error, "String is too short! (min length: 5)");
     }
 
     #[test]
@@ -3,13 +3,15 @@ use log::{debug, error};
 use std::cell::RefCell;
 use std::rc::Rc;
 use yaml_schema::validation::ValidationError;
-use yaml_schema::{Engine, Result, RootSchema};
+use yaml_schema::{Engine, EngineOptions, Result, RootSchema, UnknownFormat};
 
 #[derive(Debug, Default, World)]
 pub struct BasicsWorld {
     root_schema: RootSchema,
     yaml_schema_error: Option<yaml_schema::Error>,
     errors: Option<Rc<RefCell<Vec<ValidationError>>>>,
+    format_assertion: bool,
+    unknown_format: UnknownFormat,
 }
 
 #[given(regex = "a YAML schema:")]
@@ -25,8 +27,23 @@ async fn a_yaml_schema(world: &mut BasicsWorld, step: &Step) {
     }
 }
 
+#[given(regex = "format assertion is enabled")]
+async fn format_assertion_is_enabled(world: &mut BasicsWorld) {
+    world.format_assertion = true;
+}
+
+#[given(regex = "unknown formats are errors")]
+async fn unknown_formats_are_errors(world: &mut BasicsWorld) {
+    world.unknown_format = UnknownFormat::Error;
+}
+
 fn evaluate(world: &mut BasicsWorld, s: &str) -> Result<bool> {
-    let context = Engine::evaluate(&world.root_schema, s, false)?;
+    let options = EngineOptions {
+        format_assertion: world.format_assertion,
+        unknown_format: world.unknown_format,
+        ..Default::default()
+    };
+    let context = Engine::evaluate_with_options(&world.root_schema, s, &options)?;
     world.errors = Some(context.errors.clone());
     for error in context.errors.borrow().iter() {
         println!("{}", error);
@@ -90,4 +107,5 @@ async fn main() {
     BasicsWorld::run("features/validation/objects.feature").await;
     BasicsWorld::run("features/validation/strings.feature").await;
     BasicsWorld::run("features/composition.feature").await;
+    BasicsWorld::run("features/references.feature").await;
 }
@@ -1,15 +1,29 @@
 use core::panic;
 use cucumber::{gherkin::Step, given, then, when, World};
 use log::{debug, info};
+use std::fs::File;
 use std::process::Command;
+use std::process::Stdio;
 
 #[derive(Debug, Default, World)]
 pub struct CliWorld {
     command_output: Option<String>,
+    command_stderr: Option<String>,
+    exit_code: Option<i32>,
 }
 
-#[when(regex = "the following command is run:")]
+#[when(regex = "^the following command is run:$")]
 async fn run_command(world: &mut CliWorld, step: &Step) {
+    execute(world, step, Stdio::null());
+}
+
+#[when(expr = "the following command is run with `{word}` piped to stdin:")]
+async fn run_command_with_stdin(world: &mut CliWorld, filename: String, step: &Step) {
+    let file = File::open(&filename).unwrap();
+    execute(world, step, Stdio::from(file));
+}
+
+fn execute(world: &mut CliWorld, step: &Step, stdin: Stdio) {
     let raw_command = step.docstring().unwrap();
     debug!("raw_command {}", raw_command);
     let parts = raw_command.split_whitespace().collect::<Vec<&str>>();
@@ -24,11 +38,13 @@ async fn run_command(world: &mut CliWorld, step: &Step) {
     };
     debug!("Executable: {}", executable);
 
-    match Command::new(executable).args(args).output() {
+    match Command::new(executable).args(args).stdin(stdin).output() {
         Ok(output) => {
             let output_str = String::from_utf8(output.stdout).unwrap();
             debug!("Output: {}", output_str);
             world.command_output = Some(output_str);
+            world.command_stderr = Some(String::from_utf8(output.stderr).unwrap());
+            world.exit_code = output.status.code();
         }
         Err(e) => {
             panic!("Failed to run command: {}", e);
@@ -44,8 +60,9 @@ async fn a_file_containing(_world: &mut CliWorld, filename: String, step: &Step)
 }
 
 #[then(expr = "it should exit with status code {int}")]
-async fn it_should_exit_with_status(_world: &mut CliWorld, status: i32) {
+async fn it_should_exit_with_status(world: &mut CliWorld, status: i32) {
     debug!("status: {:?}", status);
+    assert_eq!(world.exit_code, Some(status));
 }
 
 #[then(expr = "it should output:")]
@@ -57,6 +74,25 @@ async fn it_should_output(world: &mut CliWorld, step: &Step) {
     assert_eq!(expected_output, actual_output);
 }
 
+#[then(expr = "it should print to stderr:")]
+async fn it_should_print_to_stderr(world: &mut CliWorld, step: &Step) {
+    // stderr also has the output of `cargo run`, so only check that it contains each line
+    let stderr = world.command_stderr.as_ref().unwrap();
+    for line in step
+        .docstring()
+        .unwrap()
+        .lines()
+        .filter(|line| !line.is_empty())
+    {
+        assert!(
+            stderr.contains(line),
+            "{:?} not found in stderr: {}",
+            line,
+            stderr
+        );
+    }
+}
+
 #[tokio::main]
 async fn main() {
     env_logger::builder()